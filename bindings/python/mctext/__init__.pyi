@@ -1,4 +1,4 @@
-from typing import List, Optional, Tuple
+from typing import List, Optional, Tuple, Union
 
 class Style:
     bold: bool
@@ -7,6 +7,15 @@ class Style:
     strikethrough: bool
     obfuscated: bool
 
+    def __init__(
+        self,
+        bold: bool = False,
+        italic: bool = False,
+        underlined: bool = False,
+        strikethrough: bool = False,
+        obfuscated: bool = False,
+    ) -> None: ...
+
 class Color:
     r: int
     g: int
@@ -16,15 +25,35 @@ class Color:
     code: Optional[str]
     is_named: bool
 
+    def __init__(self, color: str) -> None: ...
+    @staticmethod
+    def from_name(name: str) -> Color: ...
+    @staticmethod
+    def from_hex(hex: str) -> Color: ...
+    @staticmethod
+    def from_rgb(r: int, g: int, b: int) -> Color: ...
     def to_hex(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
 
 class Span:
     text: str
     color: Optional[Color]
     style: Style
+    font: Optional[str]
+    insertion: Optional[str]
+
+    def __init__(
+        self,
+        text: str,
+        color: Optional[Color] = None,
+        style: Optional[Style] = None,
+        font: Optional[str] = None,
+        insertion: Optional[str] = None,
+    ) -> None: ...
 
 class SpanBuilder:
-    def color(self, color: str) -> SpanBuilder: ...
+    def color(self, color: Union[Color, str]) -> SpanBuilder: ...
     def bold(self) -> SpanBuilder: ...
     def italic(self) -> SpanBuilder: ...
     def underlined(self) -> SpanBuilder: ...
@@ -38,6 +67,9 @@ class MCText:
     @staticmethod
     def parse(text: str) -> MCText: ...
     @staticmethod
+    def from_spans(spans: List[Span]) -> MCText: ...
+    def push(self, span: Span) -> None: ...
+    @staticmethod
     def parse_json(json: str) -> MCText: ...
     def plain_text(self) -> str: ...
     def to_legacy(self) -> str: ...
@@ -46,6 +78,8 @@ class MCText:
     def span(self, text: str) -> SpanBuilder: ...
     def concat(self, other: MCText) -> MCText: ...
     def is_empty(self) -> bool: ...
+    def byte_len(self) -> int: ...
+    def utf16_len(self) -> int: ...
     def __len__(self) -> int: ...
     def __str__(self) -> str: ...
 
@@ -67,14 +101,38 @@ class FontSystem:
     def legacy() -> FontSystem: ...
     def measure(self, text: str, size: float) -> float: ...
     def measure_family(self, text: str, size: float, family: FontFamily) -> float: ...
-    def ascent_ratio(self) -> float: ...
+    def ascent_ratio(self, bold: bool = False, italic: bool = False) -> float: ...
+    def line_metrics(
+        self, size: float, bold: bool = False, italic: bool = False
+    ) -> Tuple[float, float, float]: ...
+    def layout(self, text: MCText, options: LayoutOptions) -> TextLayout: ...
 
 class LayoutOptions:
     def __init__(self, size: float) -> None: ...
     def with_max_width(self, width: float) -> LayoutOptions: ...
     def with_shadow(self, shadow: bool) -> LayoutOptions: ...
     def with_align(self, align: str) -> LayoutOptions: ...
+    def with_vertical_anchor(self, anchor: str) -> LayoutOptions: ...
     def with_line_spacing(self, spacing: float) -> LayoutOptions: ...
+    def with_letter_spacing(self, spacing: float) -> LayoutOptions: ...
+
+class PositionedGlyph:
+    char: str
+    x: float
+    y: float
+    advance: float
+    size: float
+    color: Color
+    bold: bool
+    italic: bool
+    is_shadow: bool
+
+class TextLayout:
+    width: float
+    height: float
+    line_count: int
+    def glyphs(self) -> List[PositionedGlyph]: ...
+    def __len__(self) -> int: ...
 
 class RenderResult:
     width: int
@@ -89,6 +147,15 @@ def render(
     height: int,
     options: LayoutOptions,
 ) -> RenderResult: ...
+def blend_image_over(
+    dst: bytes,
+    dst_width: int,
+    src: bytes,
+    src_width: int,
+    src_height: int,
+    x: int,
+    y: int,
+) -> bytes: ...
 def render_family(
     font_system: FontSystem,
     text: str,
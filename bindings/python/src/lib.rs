@@ -7,15 +7,15 @@ use pyo3::prelude::*;
 #[pyclass]
 #[derive(Clone)]
 pub struct Style {
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     bold: bool,
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     italic: bool,
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     underlined: bool,
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     strikethrough: bool,
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     obfuscated: bool,
 }
 
@@ -31,8 +31,38 @@ impl From<&RustStyle> for Style {
     }
 }
 
+impl From<&Style> for RustStyle {
+    fn from(s: &Style) -> Self {
+        RustStyle {
+            bold: s.bold,
+            italic: s.italic,
+            underlined: s.underlined,
+            strikethrough: s.strikethrough,
+            obfuscated: s.obfuscated,
+        }
+    }
+}
+
 #[pymethods]
 impl Style {
+    #[new]
+    #[pyo3(signature = (bold=false, italic=false, underlined=false, strikethrough=false, obfuscated=false))]
+    fn new(
+        bold: bool,
+        italic: bool,
+        underlined: bool,
+        strikethrough: bool,
+        obfuscated: bool,
+    ) -> Self {
+        Style {
+            bold,
+            italic,
+            underlined,
+            strikethrough,
+            obfuscated,
+        }
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "Style(bold={}, italic={}, underlined={}, strikethrough={}, obfuscated={})",
@@ -49,6 +79,40 @@ pub struct Color {
 
 #[pymethods]
 impl Color {
+    #[new]
+    fn new(color: &str) -> PyResult<Self> {
+        TextColor::parse(color)
+            .map(|inner| Color { inner })
+            .ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!("invalid color: {}", color))
+            })
+    }
+
+    #[staticmethod]
+    fn from_name(name: &str) -> PyResult<Self> {
+        NamedColor::from_name(name)
+            .map(|n| Color {
+                inner: TextColor::Named(n),
+            })
+            .ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!("unknown color name: {}", name))
+            })
+    }
+
+    #[staticmethod]
+    fn from_hex(hex: &str) -> PyResult<Self> {
+        TextColor::from_hex(hex)
+            .map(|inner| Color { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    #[staticmethod]
+    fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Color {
+            inner: TextColor::Rgb { r, g, b },
+        }
+    }
+
     #[getter]
     fn r(&self) -> u8 {
         self.inner.rgb().0
@@ -94,6 +158,15 @@ impl Color {
         self.inner.to_hex()
     }
 
+    fn __eq__(&self, other: &Color) -> bool {
+        self.inner.rgb() == other.inner.rgb()
+    }
+
+    fn __hash__(&self) -> u64 {
+        let (r, g, b) = self.inner.rgb();
+        (u64::from(r) << 16) | (u64::from(g) << 8) | u64::from(b)
+    }
+
     fn __repr__(&self) -> String {
         match self.inner {
             TextColor::Named(n) => format!("Color(name='{}')", n.name()),
@@ -105,24 +178,58 @@ impl Color {
 #[pyclass]
 #[derive(Clone)]
 pub struct Span {
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     text: String,
     color: Option<Color>,
     style: Style,
+    #[pyo3(get, set)]
+    font: Option<String>,
+    #[pyo3(get, set)]
+    insertion: Option<String>,
+    source: RustSpan,
 }
 
 #[pymethods]
 impl Span {
+    #[new]
+    #[pyo3(signature = (text, color=None, style=None, font=None, insertion=None))]
+    fn new(
+        text: String,
+        color: Option<Color>,
+        style: Option<Style>,
+        font: Option<String>,
+        insertion: Option<String>,
+    ) -> Self {
+        Span {
+            text,
+            color,
+            style: style.unwrap_or_else(|| Style::from(&RustStyle::default())),
+            font,
+            insertion,
+            source: RustSpan::default(),
+        }
+    }
+
     #[getter]
     fn color(&self) -> Option<Color> {
         self.color.clone()
     }
 
+    #[setter]
+    fn set_color(&mut self, color: Option<Color>) {
+        self.color = color;
+    }
+
     #[getter]
     fn style(&self) -> Style {
         self.style.clone()
     }
 
+    #[setter]
+    fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "Span(text='{}', color={:?}, style={:?})",
@@ -139,6 +246,22 @@ impl From<&RustSpan> for Span {
             text: s.text.clone(),
             color: s.color.map(|c| Color { inner: c }),
             style: Style::from(&s.style),
+            font: s.font.clone(),
+            insertion: s.insertion.clone(),
+            source: s.clone(),
+        }
+    }
+}
+
+impl From<&Span> for RustSpan {
+    fn from(s: &Span) -> Self {
+        RustSpan {
+            text: s.text.clone(),
+            color: s.color.as_ref().map(|c| c.inner),
+            style: RustStyle::from(&s.style),
+            font: s.font.clone(),
+            insertion: s.insertion.clone(),
+            ..s.source.clone()
         }
     }
 }
@@ -164,6 +287,17 @@ impl MCText {
         }
     }
 
+    #[staticmethod]
+    fn from_spans(spans: Vec<Span>) -> Self {
+        Self {
+            inner: spans.iter().map(RustSpan::from).collect(),
+        }
+    }
+
+    fn push(&mut self, span: &Span) {
+        self.inner.extend([RustSpan::from(span)]);
+    }
+
     #[staticmethod]
     fn parse_json(json: &str) -> PyResult<Self> {
         ::mctext::try_parse_json_component(json)
@@ -203,6 +337,14 @@ impl MCText {
         self.inner.is_empty()
     }
 
+    fn byte_len(&self) -> usize {
+        self.inner.byte_len()
+    }
+
+    fn utf16_len(&self) -> usize {
+        self.inner.utf16_len()
+    }
+
     fn concat(&self, other: &MCText) -> MCText {
         MCText {
             inner: self.inner.clone().concat(other.inner.clone()),
@@ -216,6 +358,12 @@ impl MCText {
     }
 }
 
+#[derive(FromPyObject)]
+enum ColorArg {
+    Color(Color),
+    Name(String),
+}
+
 #[pyclass]
 pub struct SpanBuilder {
     inner: Option<RustSpanBuilder>,
@@ -223,15 +371,16 @@ pub struct SpanBuilder {
 
 #[pymethods]
 impl SpanBuilder {
-    fn color(&mut self, color: &str) -> SpanBuilder {
+    fn color(&mut self, color: ColorArg) -> SpanBuilder {
         let inner = self.inner.take();
+        let parsed = match color {
+            ColorArg::Color(color) => Some(color.inner),
+            ColorArg::Name(name) => TextColor::parse(&name),
+        };
         SpanBuilder {
-            inner: inner.map(|b| {
-                if let Some(parsed) = TextColor::parse(color) {
-                    b.color(parsed)
-                } else {
-                    b
-                }
+            inner: inner.map(|b| match parsed {
+                Some(parsed) => b.color(parsed),
+                None => b,
             }),
         }
     }
@@ -303,7 +452,8 @@ mod rendering {
     use super::*;
     use ::mctext::{
         FontFamily as RustFontFamily, FontSystem as RustFontSystem, FontVariant, FontVersion,
-        LayoutOptions as RustLayoutOptions, SoftwareRenderer, TextRenderContext,
+        LayoutEngine, LayoutOptions as RustLayoutOptions, PositionedGlyph as RustPositionedGlyph,
+        SoftwareRenderer, TextRenderContext, blend_image_over as rust_blend_image_over,
     };
 
     #[pyclass(eq, eq_int)]
@@ -357,8 +507,99 @@ mod rendering {
             self.inner.measure_text_family(text, size, family.into())
         }
 
-        fn ascent_ratio(&self) -> f32 {
-            self.inner.ascent_ratio(FontVariant::Regular)
+        #[pyo3(signature = (bold=false, italic=false))]
+        fn ascent_ratio(&self, bold: bool, italic: bool) -> f32 {
+            self.inner
+                .ascent_ratio(FontVariant::from_style(bold, italic))
+        }
+
+        #[pyo3(signature = (size, bold=false, italic=false))]
+        fn line_metrics(&self, size: f32, bold: bool, italic: bool) -> (f32, f32, f32) {
+            let metrics = self
+                .inner
+                .line_metrics(size, FontVariant::from_style(bold, italic));
+            (metrics.ascent, metrics.descent, metrics.line_gap)
+        }
+
+        fn layout(&self, text: &MCText, options: &LayoutOptions) -> TextLayout {
+            let layout = LayoutEngine::new(&self.inner).layout(&text.inner, &options.to_rust());
+            TextLayout {
+                width: layout.width,
+                height: layout.height,
+                line_count: layout.line_count,
+                glyphs: layout.glyphs.iter().map(PositionedGlyph::from).collect(),
+            }
+        }
+    }
+
+    #[pyclass]
+    #[derive(Clone)]
+    pub struct PositionedGlyph {
+        #[pyo3(get, name = "char")]
+        ch: char,
+        #[pyo3(get)]
+        x: f32,
+        #[pyo3(get)]
+        y: f32,
+        #[pyo3(get)]
+        advance: f32,
+        #[pyo3(get)]
+        size: f32,
+        #[pyo3(get)]
+        color: Color,
+        #[pyo3(get)]
+        bold: bool,
+        #[pyo3(get)]
+        italic: bool,
+        #[pyo3(get)]
+        is_shadow: bool,
+    }
+
+    impl From<&RustPositionedGlyph> for PositionedGlyph {
+        fn from(g: &RustPositionedGlyph) -> Self {
+            PositionedGlyph {
+                ch: g.ch,
+                x: g.x,
+                y: g.y,
+                advance: g.advance,
+                size: g.size,
+                color: Color { inner: g.color },
+                bold: g.variant.is_bold(),
+                italic: matches!(g.variant, FontVariant::Italic | FontVariant::BoldItalic),
+                is_shadow: g.is_shadow,
+            }
+        }
+    }
+
+    #[pymethods]
+    impl PositionedGlyph {
+        fn __repr__(&self) -> String {
+            format!(
+                "PositionedGlyph(char={:?}, x={}, y={}, advance={}, size={})",
+                self.ch, self.x, self.y, self.advance, self.size
+            )
+        }
+    }
+
+    #[pyclass]
+    pub struct TextLayout {
+        #[pyo3(get)]
+        width: f32,
+        #[pyo3(get)]
+        height: f32,
+        #[pyo3(get)]
+        line_count: usize,
+        glyphs: Vec<PositionedGlyph>,
+    }
+
+    #[pymethods]
+    impl TextLayout {
+        fn glyphs(&self) -> Vec<PositionedGlyph> {
+            self.glyphs.clone()
+        }
+
+        fn __len__(&self) -> usize {
+            self.glyphs.len()
         }
     }
 
@@ -369,26 +610,35 @@ mod rendering {
         max_width: Option<f32>,
         shadow: bool,
         align: String,
+        vertical_anchor: String,
         line_spacing: f32,
+        letter_spacing: f32,
     }
 
     #[pymethods]
     impl LayoutOptions {
         #[new]
-        fn new(size: f32) -> Self {
-            Self {
+        fn new(size: f32) -> PyResult<Self> {
+            RustLayoutOptions::try_new(size)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+            Ok(Self {
                 size,
                 max_width: None,
                 shadow: false,
                 align: "left".to_string(),
+                vertical_anchor: "top".to_string(),
                 line_spacing: -1.0,
-            }
+                letter_spacing: 0.0,
+            })
         }
 
-        fn with_max_width(&self, width: f32) -> Self {
+        fn with_max_width(&self, width: f32) -> PyResult<Self> {
+            RustLayoutOptions::new(self.size)
+                .try_with_max_width(width)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
             let mut opts = self.clone();
             opts.max_width = Some(width);
-            opts
+            Ok(opts)
         }
 
         fn with_shadow(&self, shadow: bool) -> Self {
@@ -403,27 +653,46 @@ mod rendering {
             opts
         }
 
+        fn with_vertical_anchor(&self, anchor: &str) -> Self {
+            let mut opts = self.clone();
+            opts.vertical_anchor = anchor.to_string();
+            opts
+        }
+
         fn with_line_spacing(&self, spacing: f32) -> Self {
             let mut opts = self.clone();
             opts.line_spacing = spacing;
             opts
         }
+
+        fn with_letter_spacing(&self, spacing: f32) -> Self {
+            let mut opts = self.clone();
+            opts.letter_spacing = spacing;
+            opts
+        }
     }
 
     impl LayoutOptions {
         fn to_rust(&self) -> RustLayoutOptions {
-            use ::mctext::TextAlign;
+            use ::mctext::{TextAlign, VerticalAnchor};
             let mut opts = RustLayoutOptions::new(self.size);
             if let Some(w) = self.max_width {
                 opts = opts.with_max_width(w);
             }
             opts = opts.with_shadow(self.shadow);
             opts = opts.with_line_spacing(self.line_spacing);
+            opts = opts.with_letter_spacing(self.letter_spacing);
             opts = opts.with_align(match self.align.as_str() {
                 "center" => TextAlign::Center,
                 "right" => TextAlign::Right,
                 _ => TextAlign::Left,
             });
+            opts = opts.with_vertical_anchor(match self.vertical_anchor.as_str() {
+                "baseline" => VerticalAnchor::Baseline,
+                "middle" => VerticalAnchor::Middle,
+                "bottom" => VerticalAnchor::Bottom,
+                _ => VerticalAnchor::Top,
+            });
             opts
         }
     }
@@ -480,7 +749,9 @@ mod rendering {
         height: u32,
         size: f32,
         family: FontFamily,
-    ) -> RenderResult {
+    ) -> PyResult<RenderResult> {
+        RustLayoutOptions::try_new(size)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
         let (w, h) = (width as usize, height as usize);
         let mut buffer = vec![0u8; w * h * 4];
         let rust_family: RustFontFamily = family.into();
@@ -491,53 +762,65 @@ mod rendering {
             .unwrap_or(size * 0.8);
 
         let mut x = 0.0f32;
-        for ch in text.chars() {
-            if ch.is_control() {
-                continue;
-            }
+        for span in RustMCText::parse(text).spans() {
+            let (r, g, b) = span.color.unwrap_or_default().rgb();
 
-            let (metrics, bitmap) = font.rasterize(ch, size);
-            let gx = (x + metrics.xmin as f32) as i32;
-            let gy = (ascent - metrics.ymin as f32 - metrics.height as f32) as i32;
-
-            for row in 0..metrics.height {
-                for col in 0..metrics.width {
-                    let px = gx + col as i32;
-                    let py = gy + row as i32;
-                    if px >= 0 && px < w as i32 && py >= 0 && py < h as i32 {
-                        let src = bitmap[row * metrics.width + col];
-                        if src > 0 {
-                            let idx = ((py as usize) * w + (px as usize)) * 4;
-                            buffer[idx] = 255;
-                            buffer[idx + 1] = 255;
-                            buffer[idx + 2] = 255;
-                            buffer[idx + 3] = src;
-                        }
-                    }
+            for ch in span.text.chars() {
+                if ch.is_control() {
+                    continue;
                 }
-            }
 
-            x += if ch == ' ' {
-                size * 0.4
-            } else {
-                metrics.advance_width
-            };
+                let (metrics, bitmap) = font.rasterize(ch, size);
+                let gx = (x + metrics.xmin as f32) as i32;
+                let gy = (ascent - metrics.ymin as f32 - metrics.height as f32) as i32;
+
+                let layer: Vec<u8> = bitmap.iter().flat_map(|&a| [r, g, b, a]).collect();
+                rust_blend_image_over(
+                    &mut buffer,
+                    w,
+                    &layer,
+                    metrics.width,
+                    metrics.height,
+                    gx,
+                    gy,
+                );
+
+                x += font_system.inner.measure_char_family(ch, size, rust_family);
+            }
         }
 
-        RenderResult {
+        Ok(RenderResult {
             width,
             height,
             data: buffer,
-        }
+        })
+    }
+
+    #[pyfunction]
+    pub fn blend_image_over(
+        dst: Vec<u8>,
+        dst_width: usize,
+        src: Vec<u8>,
+        src_width: usize,
+        src_height: usize,
+        x: i32,
+        y: i32,
+    ) -> Vec<u8> {
+        let mut dst = dst;
+        rust_blend_image_over(&mut dst, dst_width, &src, src_width, src_height, x, y);
+        dst
     }
 
     pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
         m.add_class::<FontFamily>()?;
         m.add_class::<FontSystem>()?;
         m.add_class::<LayoutOptions>()?;
+        m.add_class::<PositionedGlyph>()?;
+        m.add_class::<TextLayout>()?;
         m.add_class::<RenderResult>()?;
         m.add_function(wrap_pyfunction!(render, m)?)?;
         m.add_function(wrap_pyfunction!(render_family, m)?)?;
+        m.add_function(wrap_pyfunction!(blend_image_over, m)?)?;
         Ok(())
     }
 }
@@ -1,6 +1,6 @@
 use mctext::{
-    MCText as RustMCText, NamedColor, Span as RustSpan, SpanBuilder as RustSpanBuilder,
-    Style as RustStyle, TextColor,
+    ClickEvent, ComponentContent, HoverEvent, MCText as RustMCText, NamedColor, Span as RustSpan,
+    SpanBuilder as RustSpanBuilder, Style as RustStyle, TextColor, Translation,
 };
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -11,10 +11,23 @@ pub struct MCText {
 }
 
 #[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Span {
     pub text: String,
     pub color: Option<Color>,
     pub style: Style,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub click_event: Option<ClickEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hover_event: Option<HoverEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translate: Option<Translation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<ComponentContent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insertion: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -22,7 +35,9 @@ pub struct Span {
 pub enum Color {
     Named {
         name: String,
+        #[serde(default)]
         code: char,
+        #[serde(default)]
         rgb: [u8; 3],
     },
     Rgb {
@@ -75,10 +90,69 @@ impl From<&RustSpan> for Span {
             text: s.text.clone(),
             color: s.color.map(Color::from),
             style: Style::from(&s.style),
+            click_event: s.click_event.clone(),
+            hover_event: s.hover_event.clone(),
+            translate: s.translate.clone(),
+            content: s.content.clone(),
+            font: s.font.clone(),
+            insertion: s.insertion.clone(),
         }
     }
 }
 
+impl From<&Style> for RustStyle {
+    fn from(s: &Style) -> Self {
+        RustStyle {
+            bold: s.bold,
+            italic: s.italic,
+            underlined: s.underlined,
+            strikethrough: s.strikethrough,
+            obfuscated: s.obfuscated,
+        }
+    }
+}
+
+impl TryFrom<&Color> for TextColor {
+    type Error = JsError;
+
+    fn try_from(c: &Color) -> Result<Self, Self::Error> {
+        match c {
+            Color::Named { name, .. } => NamedColor::from_name(name)
+                .map(TextColor::Named)
+                .ok_or_else(|| JsError::new(&format!("unknown color name: {}", name))),
+            Color::Rgb { r, g, b } => Ok(TextColor::Rgb {
+                r: *r,
+                g: *g,
+                b: *b,
+            }),
+        }
+    }
+}
+
+impl TryFrom<&Span> for RustSpan {
+    type Error = JsError;
+
+    fn try_from(s: &Span) -> Result<Self, Self::Error> {
+        Ok(RustSpan {
+            text: s.text.clone(),
+            color: s.color.as_ref().map(TextColor::try_from).transpose()?,
+            style: RustStyle::from(&s.style),
+            click_event: s.click_event.clone(),
+            hover_event: s.hover_event.clone(),
+            translate: s.translate.clone(),
+            content: s.content.clone(),
+            font: s.font.clone(),
+            insertion: s.insertion.clone(),
+        })
+    }
+}
+
+fn spans_from_js(spans: JsValue) -> Result<Vec<RustSpan>, JsError> {
+    let spans: Vec<Span> =
+        serde_wasm_bindgen::from_value(spans).map_err(|e| JsError::new(&e.to_string()))?;
+    spans.iter().map(RustSpan::try_from).collect()
+}
+
 #[wasm_bindgen]
 impl MCText {
     #[wasm_bindgen(constructor)]
@@ -111,6 +185,16 @@ impl MCText {
         self.inner.to_legacy()
     }
 
+    #[wasm_bindgen(js_name = toHtml)]
+    pub fn to_html(&self) -> String {
+        self.inner.to_html()
+    }
+
+    #[wasm_bindgen(js_name = toAnsi)]
+    pub fn to_ansi(&self) -> String {
+        self.inner.to_ansi()
+    }
+
     #[wasm_bindgen(js_name = toJson)]
     pub fn to_json(&self) -> String {
         mctext::to_json(&self.inner)
@@ -121,11 +205,34 @@ impl MCText {
         serde_wasm_bindgen::to_value(&spans).unwrap_or(JsValue::NULL)
     }
 
+    #[wasm_bindgen(js_name = fromSpans)]
+    pub fn from_spans(spans: JsValue) -> Result<MCText, JsError> {
+        Ok(Self {
+            inner: RustMCText::from_spans(spans_from_js(spans)?),
+        })
+    }
+
+    #[wasm_bindgen(js_name = setSpans)]
+    pub fn set_spans(&mut self, spans: JsValue) -> Result<(), JsError> {
+        self.inner = RustMCText::from_spans(spans_from_js(spans)?);
+        Ok(())
+    }
+
     #[wasm_bindgen(js_name = isEmpty)]
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
 
+    #[wasm_bindgen(js_name = byteLen)]
+    pub fn byte_len(&self) -> usize {
+        self.inner.byte_len()
+    }
+
+    #[wasm_bindgen(js_name = utf16Len)]
+    pub fn utf16_len(&self) -> usize {
+        self.inner.utf16_len()
+    }
+
     pub fn concat(&self, other: &MCText) -> MCText {
         MCText {
             inner: self.inner.clone().concat(other.inner.clone()),
@@ -243,7 +350,7 @@ mod render {
     use super::*;
     use mctext::{
         FontFamily as RustFontFamily, FontSystem as RustFontSystem, FontVersion,
-        LayoutOptions as RustLayoutOptions,
+        LayoutOptions as RustLayoutOptions, blend_image_over as rust_blend_image_over,
     };
 
     #[wasm_bindgen]
@@ -296,11 +403,51 @@ mod render {
             self.inner.measure_text_family(text, size, family.into())
         }
 
+        #[wasm_bindgen(js_name = measureBlock)]
+        pub fn measure_block(&self, text: &str, size: f32, max_width: f32) -> Vec<f32> {
+            let (width, height) = self.inner.measure_block(text, size, max_width);
+            vec![width, height]
+        }
+
+        #[wasm_bindgen(js_name = missingGlyphs)]
+        pub fn missing_glyphs(
+            &self,
+            text: &str,
+            family: FontFamily,
+            bold: bool,
+            italic: bool,
+        ) -> JsValue {
+            use mctext::FontVariant;
+            let missing: Vec<String> = self
+                .inner
+                .missing_glyphs(text, family.into(), FontVariant::from_style(bold, italic))
+                .into_iter()
+                .map(String::from)
+                .collect();
+            serde_wasm_bindgen::to_value(&missing).unwrap_or(JsValue::NULL)
+        }
+
         #[wasm_bindgen(js_name = ascentRatio)]
         pub fn ascent_ratio(&self) -> f32 {
             use mctext::FontVariant;
             self.inner.ascent_ratio(FontVariant::Regular)
         }
+
+        #[wasm_bindgen(js_name = ascentRatioStyled)]
+        pub fn ascent_ratio_styled(&self, bold: bool, italic: bool) -> f32 {
+            use mctext::FontVariant;
+            self.inner
+                .ascent_ratio(FontVariant::from_style(bold, italic))
+        }
+
+        #[wasm_bindgen(js_name = lineMetrics)]
+        pub fn line_metrics(&self, size: f32, bold: bool, italic: bool) -> Vec<f32> {
+            use mctext::FontVariant;
+            let metrics = self
+                .inner
+                .line_metrics(size, FontVariant::from_style(bold, italic));
+            vec![metrics.ascent, metrics.descent, metrics.line_gap]
+        }
     }
 
     #[wasm_bindgen]
@@ -310,27 +457,35 @@ mod render {
         max_width: Option<f32>,
         shadow: bool,
         align: String,
+        vertical_anchor: String,
         line_spacing: f32,
+        letter_spacing: f32,
     }
 
     #[wasm_bindgen]
     impl LayoutOptions {
         #[wasm_bindgen(constructor)]
-        pub fn new(size: f32) -> Self {
-            Self {
+        pub fn new(size: f32) -> Result<LayoutOptions, JsError> {
+            RustLayoutOptions::try_new(size).map_err(|e| JsError::new(&e.to_string()))?;
+            Ok(Self {
                 size,
                 max_width: None,
                 shadow: false,
                 align: "left".to_string(),
+                vertical_anchor: "top".to_string(),
                 line_spacing: -1.0,
-            }
+                letter_spacing: 0.0,
+            })
         }
 
         #[wasm_bindgen(js_name = withMaxWidth)]
-        pub fn with_max_width(&self, width: f32) -> Self {
+        pub fn with_max_width(&self, width: f32) -> Result<LayoutOptions, JsError> {
+            RustLayoutOptions::new(self.size)
+                .try_with_max_width(width)
+                .map_err(|e| JsError::new(&e.to_string()))?;
             let mut opts = self.clone();
             opts.max_width = Some(width);
-            opts
+            Ok(opts)
         }
 
         #[wasm_bindgen(js_name = withShadow)]
@@ -347,6 +502,13 @@ mod render {
             opts
         }
 
+        #[wasm_bindgen(js_name = withVerticalAnchor)]
+        pub fn with_vertical_anchor(&self, anchor: &str) -> Self {
+            let mut opts = self.clone();
+            opts.vertical_anchor = anchor.to_string();
+            opts
+        }
+
         #[wasm_bindgen(js_name = withLineSpacing)]
         pub fn with_line_spacing(&self, spacing: f32) -> Self {
             let mut opts = self.clone();
@@ -354,19 +516,33 @@ mod render {
             opts
         }
 
+        #[wasm_bindgen(js_name = withLetterSpacing)]
+        pub fn with_letter_spacing(&self, spacing: f32) -> Self {
+            let mut opts = self.clone();
+            opts.letter_spacing = spacing;
+            opts
+        }
+
         fn to_rust(&self) -> RustLayoutOptions {
-            use mctext::TextAlign;
+            use mctext::{TextAlign, VerticalAnchor};
             let mut opts = RustLayoutOptions::new(self.size);
             if let Some(w) = self.max_width {
                 opts = opts.with_max_width(w);
             }
             opts = opts.with_shadow(self.shadow);
             opts = opts.with_line_spacing(self.line_spacing);
+            opts = opts.with_letter_spacing(self.letter_spacing);
             opts = opts.with_align(match self.align.as_str() {
                 "center" => TextAlign::Center,
                 "right" => TextAlign::Right,
                 _ => TextAlign::Left,
             });
+            opts = opts.with_vertical_anchor(match self.vertical_anchor.as_str() {
+                "baseline" => VerticalAnchor::Baseline,
+                "middle" => VerticalAnchor::Middle,
+                "bottom" => VerticalAnchor::Bottom,
+                _ => VerticalAnchor::Top,
+            });
             opts
         }
     }
@@ -427,7 +603,8 @@ mod render {
         height: u32,
         size: f32,
         family: FontFamily,
-    ) -> RenderResult {
+    ) -> Result<RenderResult, JsError> {
+        RustLayoutOptions::try_new(size).map_err(|e| JsError::new(&e.to_string()))?;
         let (w, h) = (width as usize, height as usize);
         let mut buffer = vec![0u8; w * h * 4];
         let rust_family: RustFontFamily = family.into();
@@ -438,44 +615,51 @@ mod render {
             .unwrap_or(size * 0.8);
 
         let mut x = 0.0f32;
-        for ch in text.chars() {
-            if ch.is_control() {
-                continue;
-            }
+        for span in RustMCText::parse(text).spans() {
+            let (r, g, b) = span.color.unwrap_or_default().rgb();
 
-            let (metrics, bitmap) = font.rasterize(ch, size);
-            let gx = (x + metrics.xmin as f32) as i32;
-            let gy = (ascent - metrics.ymin as f32 - metrics.height as f32) as i32;
-
-            for row in 0..metrics.height {
-                for col in 0..metrics.width {
-                    let px = gx + col as i32;
-                    let py = gy + row as i32;
-                    if px >= 0 && px < w as i32 && py >= 0 && py < h as i32 {
-                        let src = bitmap[row * metrics.width + col];
-                        if src > 0 {
-                            let idx = ((py as usize) * w + (px as usize)) * 4;
-                            buffer[idx] = 255;
-                            buffer[idx + 1] = 255;
-                            buffer[idx + 2] = 255;
-                            buffer[idx + 3] = src;
-                        }
-                    }
+            for ch in span.text.chars() {
+                if ch.is_control() {
+                    continue;
                 }
-            }
 
-            x += if ch == ' ' {
-                size * 0.4
-            } else {
-                metrics.advance_width
-            };
+                let (metrics, bitmap) = font.rasterize(ch, size);
+                let gx = (x + metrics.xmin as f32) as i32;
+                let gy = (ascent - metrics.ymin as f32 - metrics.height as f32) as i32;
+
+                let layer: Vec<u8> = bitmap.iter().flat_map(|&a| [r, g, b, a]).collect();
+                rust_blend_image_over(
+                    &mut buffer,
+                    w,
+                    &layer,
+                    metrics.width,
+                    metrics.height,
+                    gx,
+                    gy,
+                );
+
+                x += font_system.inner.measure_char_family(ch, size, rust_family);
+            }
         }
 
-        RenderResult {
+        Ok(RenderResult {
             width,
             height,
             data: buffer,
-        }
+        })
+    }
+
+    #[wasm_bindgen(js_name = blendImageOver)]
+    pub fn blend_image_over(
+        dst: &mut [u8],
+        dst_width: usize,
+        src: &[u8],
+        src_width: usize,
+        src_height: usize,
+        x: i32,
+        y: i32,
+    ) {
+        rust_blend_image_over(dst, dst_width, src, src_width, src_height, x, y);
     }
 }
 
@@ -1,5 +1,10 @@
+use crate::json::ParseError;
+use crate::math;
+use alloc::format;
+use alloc::string::{String, ToString};
+
 #[cfg(feature = "serde")]
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -29,6 +34,7 @@ struct ColorData {
     code: char,
     name: &'static str,
     rgb: (u8, u8, u8),
+    shadow: (u8, u8, u8),
 }
 
 const COLOR_TABLE: [ColorData; 16] = [
@@ -37,96 +43,112 @@ const COLOR_TABLE: [ColorData; 16] = [
         code: '0',
         name: "black",
         rgb: (0, 0, 0),
+        shadow: (0, 0, 0),
     },
     ColorData {
         color: NamedColor::DarkBlue,
         code: '1',
         name: "dark_blue",
         rgb: (0, 0, 170),
+        shadow: (0, 0, 42),
     },
     ColorData {
         color: NamedColor::DarkGreen,
         code: '2',
         name: "dark_green",
         rgb: (0, 170, 0),
+        shadow: (0, 42, 0),
     },
     ColorData {
         color: NamedColor::DarkAqua,
         code: '3',
         name: "dark_aqua",
         rgb: (0, 170, 170),
+        shadow: (0, 42, 42),
     },
     ColorData {
         color: NamedColor::DarkRed,
         code: '4',
         name: "dark_red",
         rgb: (170, 0, 0),
+        shadow: (42, 0, 0),
     },
     ColorData {
         color: NamedColor::DarkPurple,
         code: '5',
         name: "dark_purple",
         rgb: (170, 0, 170),
+        shadow: (42, 0, 42),
     },
     ColorData {
         color: NamedColor::Gold,
         code: '6',
         name: "gold",
         rgb: (255, 170, 0),
+        shadow: (63, 42, 0),
     },
     ColorData {
         color: NamedColor::Gray,
         code: '7',
         name: "gray",
         rgb: (170, 170, 170),
+        shadow: (42, 42, 42),
     },
     ColorData {
         color: NamedColor::DarkGray,
         code: '8',
         name: "dark_gray",
         rgb: (85, 85, 85),
+        shadow: (21, 21, 21),
     },
     ColorData {
         color: NamedColor::Blue,
         code: '9',
         name: "blue",
         rgb: (85, 85, 255),
+        shadow: (21, 21, 63),
     },
     ColorData {
         color: NamedColor::Green,
         code: 'a',
         name: "green",
         rgb: (85, 255, 85),
+        shadow: (21, 63, 21),
     },
     ColorData {
         color: NamedColor::Aqua,
         code: 'b',
         name: "aqua",
         rgb: (85, 255, 255),
+        shadow: (21, 63, 63),
     },
     ColorData {
         color: NamedColor::Red,
         code: 'c',
         name: "red",
         rgb: (255, 85, 85),
+        shadow: (63, 21, 21),
     },
     ColorData {
         color: NamedColor::LightPurple,
         code: 'd',
         name: "light_purple",
         rgb: (255, 85, 255),
+        shadow: (63, 21, 63),
     },
     ColorData {
         color: NamedColor::Yellow,
         code: 'e',
         name: "yellow",
         rgb: (255, 255, 85),
+        shadow: (63, 63, 21),
     },
     ColorData {
         color: NamedColor::White,
         code: 'f',
         name: "white",
         rgb: (255, 255, 255),
+        shadow: (63, 63, 63),
     },
 ];
 
@@ -150,6 +172,22 @@ impl NamedColor {
         NamedColor::White,
     ];
 
+    pub const READABLE: [NamedColor; 13] = [
+        NamedColor::DarkGreen,
+        NamedColor::DarkAqua,
+        NamedColor::DarkRed,
+        NamedColor::DarkPurple,
+        NamedColor::Gold,
+        NamedColor::Gray,
+        NamedColor::Blue,
+        NamedColor::Green,
+        NamedColor::Aqua,
+        NamedColor::Red,
+        NamedColor::LightPurple,
+        NamedColor::Yellow,
+        NamedColor::White,
+    ];
+
     fn data(self) -> &'static ColorData {
         &COLOR_TABLE[self as usize]
     }
@@ -158,6 +196,10 @@ impl NamedColor {
         self.data().rgb
     }
 
+    pub fn shadow_rgb(self) -> (u8, u8, u8) {
+        self.data().shadow
+    }
+
     pub fn code(self) -> char {
         self.data().code
     }
@@ -172,20 +214,164 @@ impl NamedColor {
     }
 
     pub fn from_name(name: &str) -> Option<NamedColor> {
-        let name_lower = name.to_lowercase();
-        let lookup = match name_lower.as_str() {
-            "grey" => "gray",
-            "dark_grey" => "dark_gray",
-            _ => &name_lower,
+        let normalize = |s: &str| {
+            s.chars()
+                .filter(|ch| !matches!(ch, ' ' | '_' | '-'))
+                .map(|ch| ch.to_ascii_lowercase())
+                .collect::<String>()
+                .replace("grey", "gray")
         };
+        let lookup = normalize(name);
         COLOR_TABLE
             .iter()
-            .find(|d| d.name == lookup)
+            .find(|d| normalize(d.name) == lookup)
             .map(|d| d.color)
     }
+
+    pub fn from_hash(s: &str) -> NamedColor {
+        Self::from_hash_in(s, &Self::READABLE).unwrap_or_default()
+    }
+
+    pub fn from_hash_in(s: &str, palette: &[NamedColor]) -> Option<NamedColor> {
+        if palette.is_empty() {
+            return None;
+        }
+        let index = fnv1a(s.as_bytes()) % palette.len() as u64;
+        Some(palette[index as usize])
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xCBF2_9CE4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
 }
 
+#[cfg(feature = "bedrock-colors")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BedrockColor {
+    MinecoinGold,
+    MaterialQuartz,
+    MaterialIron,
+    MaterialNetherite,
+    MaterialGold,
+    MaterialEmerald,
+    MaterialDiamond,
+    MaterialLapis,
+    MaterialAmethyst,
+    MaterialResin,
+}
+
+#[cfg(feature = "bedrock-colors")]
+struct BedrockColorData {
+    color: BedrockColor,
+    code: char,
+    name: &'static str,
+    rgb: (u8, u8, u8),
+}
+
+#[cfg(feature = "bedrock-colors")]
+const BEDROCK_TABLE: [BedrockColorData; 10] = [
+    BedrockColorData {
+        color: BedrockColor::MinecoinGold,
+        code: 'g',
+        name: "minecoin_gold",
+        rgb: (221, 214, 5),
+    },
+    BedrockColorData {
+        color: BedrockColor::MaterialQuartz,
+        code: 'h',
+        name: "material_quartz",
+        rgb: (227, 212, 209),
+    },
+    BedrockColorData {
+        color: BedrockColor::MaterialIron,
+        code: 'i',
+        name: "material_iron",
+        rgb: (206, 202, 202),
+    },
+    BedrockColorData {
+        color: BedrockColor::MaterialNetherite,
+        code: 'j',
+        name: "material_netherite",
+        rgb: (68, 58, 59),
+    },
+    BedrockColorData {
+        color: BedrockColor::MaterialGold,
+        code: 'p',
+        name: "material_gold",
+        rgb: (222, 177, 45),
+    },
+    BedrockColorData {
+        color: BedrockColor::MaterialEmerald,
+        code: 'q',
+        name: "material_emerald",
+        rgb: (71, 160, 54),
+    },
+    BedrockColorData {
+        color: BedrockColor::MaterialDiamond,
+        code: 's',
+        name: "material_diamond",
+        rgb: (44, 186, 168),
+    },
+    BedrockColorData {
+        color: BedrockColor::MaterialLapis,
+        code: 't',
+        name: "material_lapis",
+        rgb: (33, 73, 123),
+    },
+    BedrockColorData {
+        color: BedrockColor::MaterialAmethyst,
+        code: 'u',
+        name: "material_amethyst",
+        rgb: (154, 92, 198),
+    },
+    BedrockColorData {
+        color: BedrockColor::MaterialResin,
+        code: 'v',
+        name: "material_resin",
+        rgb: (235, 113, 20),
+    },
+];
+
+#[cfg(feature = "bedrock-colors")]
+impl BedrockColor {
+    fn data(self) -> &'static BedrockColorData {
+        &BEDROCK_TABLE[self as usize]
+    }
+
+    pub fn rgb(self) -> (u8, u8, u8) {
+        self.data().rgb
+    }
+
+    pub fn code(self) -> char {
+        self.data().code
+    }
+
+    pub fn name(self) -> &'static str {
+        self.data().name
+    }
+
+    pub fn from_code(code: char) -> Option<BedrockColor> {
+        let code = code.to_ascii_lowercase();
+        BEDROCK_TABLE
+            .iter()
+            .find(|d| d.code == code)
+            .map(|d| d.color)
+    }
+}
+
+#[cfg(feature = "bedrock-colors")]
+impl From<BedrockColor> for TextColor {
+    fn from(color: BedrockColor) -> Self {
+        let (r, g, b) = color.rgb();
+        TextColor::Rgb { r, g, b }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum TextColor {
     Named(NamedColor),
     Rgb { r: u8, g: u8, b: u8 },
@@ -200,19 +386,45 @@ impl TextColor {
     }
 
     pub fn shadow_rgb(self) -> (u8, u8, u8) {
-        let (r, g, b) = self.rgb();
-        shadow_color(r, g, b)
+        match self {
+            TextColor::Named(named) => named.shadow_rgb(),
+            TextColor::Rgb { r, g, b } => shadow_color(r, g, b),
+        }
     }
 
-    pub fn from_hex(hex: &str) -> Option<TextColor> {
-        let hex = hex.strip_prefix('#').unwrap_or(hex);
-        if hex.len() != 6 {
-            return None;
+    pub fn from_hex(hex: &str) -> Result<TextColor, ParseError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let invalid = || ParseError::InvalidHex(hex.to_string());
+        if !digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |s: &str| u8::from_str_radix(s, 16).map_err(|_| invalid());
+        match digits.len() {
+            6 => Ok(TextColor::Rgb {
+                r: channel(&digits[0..2])?,
+                g: channel(&digits[2..4])?,
+                b: channel(&digits[4..6])?,
+            }),
+            3 => {
+                let expand = |i: usize| channel(&digits[i..i + 1]).map(|v| v * 17);
+                Ok(TextColor::Rgb {
+                    r: expand(0)?,
+                    g: expand(1)?,
+                    b: expand(2)?,
+                })
+            }
+            _ => Err(invalid()),
         }
-        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-        Some(TextColor::Rgb { r, g, b })
+    }
+
+    pub fn from_hex_prefer_named(hex: &str) -> Result<TextColor, ParseError> {
+        let color = TextColor::from_hex(hex)?;
+        let rgb = color.rgb();
+        Ok(NamedColor::ALL
+            .into_iter()
+            .find(|named| named.rgb() == rgb)
+            .map(TextColor::Named)
+            .unwrap_or(color))
     }
 
     pub fn to_hex(self) -> String {
@@ -220,9 +432,123 @@ impl TextColor {
         format!("#{:02X}{:02X}{:02X}", r, g, b)
     }
 
+    pub fn luminance(&self) -> f32 {
+        let (r, g, b) = self.rgb();
+        0.2126 * srgb_to_linear(r) + 0.7152 * srgb_to_linear(g) + 0.0722 * srgb_to_linear(b)
+    }
+
+    pub fn contrast_ratio(&self, other: &TextColor) -> f32 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    pub fn nearest_named(self) -> NamedColor {
+        match self {
+            TextColor::Named(named) => named,
+            TextColor::Rgb { r, g, b } => {
+                let target = srgb_to_lab(r, g, b);
+                let distance = |color: &NamedColor| {
+                    let (r, g, b) = color.rgb();
+                    let (l, a, b) = srgb_to_lab(r, g, b);
+                    let (dl, da, db) = (l - target.0, a - target.1, b - target.2);
+                    dl * dl + da * da + db * db
+                };
+                NamedColor::ALL
+                    .into_iter()
+                    .min_by(|x, y| distance(x).total_cmp(&distance(y)))
+                    .unwrap_or_default()
+            }
+        }
+    }
+
+    pub fn lerp(a: &TextColor, b: &TextColor, t: f32) -> TextColor {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let (r1, g1, b1) = a.rgb();
+        let (r2, g2, b2) = b.rgb();
+        let mix = |a: u8, b: u8| math::round(a as f32 + (b as f32 - a as f32) * t) as u8;
+        TextColor::Rgb {
+            r: mix(r1, r2),
+            g: mix(g1, g2),
+            b: mix(b1, b2),
+        }
+    }
+
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> TextColor {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let c = v * s;
+        let h = math::rem_euclid(h, 360.0) / 60.0;
+        let x = c * (1.0 - math::abs(h % 2.0 - 1.0));
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = v - c;
+        let channel = |value: f32| math::round((value + m) * 255.0).clamp(0.0, 255.0) as u8;
+        TextColor::Rgb {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        }
+    }
+
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_range();
+        let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+        (hue, saturation, max)
+    }
+
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> TextColor {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let v = l + s * l.min(1.0 - l);
+        let sv = if v > 0.0 { 2.0 * (1.0 - l / v) } else { 0.0 };
+        TextColor::from_hsv(h, sv, v)
+    }
+
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_range();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max > min {
+            (max - min) / (1.0 - math::abs(2.0 * lightness - 1.0))
+        } else {
+            0.0
+        };
+        (hue, saturation, lightness)
+    }
+
+    pub fn lighten(self, amount: f32) -> TextColor {
+        let (h, s, l) = self.to_hsl();
+        TextColor::from_hsl(h, s, l + amount)
+    }
+
+    pub fn darken(self, amount: f32) -> TextColor {
+        self.lighten(-amount)
+    }
+
+    fn hue_range(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.rgb();
+        let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * math::rem_euclid((g - b) / delta, 6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (hue, max, min)
+    }
+
     pub fn parse(s: &str) -> Option<TextColor> {
         if s.starts_with('#') {
-            TextColor::from_hex(s)
+            TextColor::from_hex(s).ok()
         } else {
             NamedColor::from_name(s).map(TextColor::Named)
         }
@@ -247,24 +573,43 @@ impl Default for TextColor {
     }
 }
 
-#[cfg(feature = "serde")]
-impl Serialize for TextColor {
-    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        match self {
-            TextColor::Named(named) => serializer.serialize_str(named.name()),
-            TextColor::Rgb { r, g, b } => {
-                serializer.serialize_str(&format!("#{:02x}{:02x}{:02x}", r, g, b))
-            }
-        }
+pub(crate) fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        math::powf((c + 0.055) / 1.055, 2.4)
     }
 }
 
-#[cfg(feature = "serde")]
-impl<'de> Deserialize<'de> for TextColor {
-    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        let s = String::deserialize(deserializer)?;
-        TextColor::parse(&s).ok_or_else(|| serde::de::Error::custom("invalid color"))
-    }
+#[cfg(feature = "render")]
+pub(crate) fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * math::powf(c, 1.0 / 2.4) - 0.055
+    };
+    math::round(c * 255.0) as u8
+}
+
+fn srgb_to_lab(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+    let f = |t: f32| {
+        if t > 0.008856 {
+            math::cbrt(t)
+        } else {
+            7.787 * t + 16.0 / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
 }
 
 pub fn shadow_color(r: u8, g: u8, b: u8) -> (u8, u8, u8) {
@@ -285,16 +630,180 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_named_color_from_name_lenient() {
+        for name in [
+            "light_purple",
+            "Light Purple",
+            "lightpurple",
+            "LIGHT-PURPLE",
+        ] {
+            assert_eq!(NamedColor::from_name(name), Some(NamedColor::LightPurple));
+        }
+        assert_eq!(
+            NamedColor::from_name("Dark Grey"),
+            Some(NamedColor::DarkGray)
+        );
+        assert_eq!(NamedColor::from_code('C'), Some(NamedColor::Red));
+        assert_eq!(NamedColor::from_name("purple"), None);
+    }
+
+    #[test]
+    fn test_from_hash() {
+        assert_eq!(fnv1a(b"a"), 0xAF63_DC4C_8601_EC8C);
+        let color = NamedColor::from_hash("Notch");
+        assert_eq!(color, NamedColor::from_hash("Notch"));
+        assert!(NamedColor::READABLE.contains(&color));
+
+        let palette = [NamedColor::Red, NamedColor::Blue];
+        let picked = NamedColor::from_hash_in("Notch", &palette).unwrap();
+        assert!(palette.contains(&picked));
+        assert_eq!(NamedColor::from_hash_in("Notch", &[]), None);
+    }
+
+    #[test]
+    fn test_named_color_table() {
+        let expected = [
+            ("#000000", "#000000"),
+            ("#0000AA", "#00002A"),
+            ("#00AA00", "#002A00"),
+            ("#00AAAA", "#002A2A"),
+            ("#AA0000", "#2A0000"),
+            ("#AA00AA", "#2A002A"),
+            ("#FFAA00", "#3F2A00"),
+            ("#AAAAAA", "#2A2A2A"),
+            ("#555555", "#151515"),
+            ("#5555FF", "#15153F"),
+            ("#55FF55", "#153F15"),
+            ("#55FFFF", "#153F3F"),
+            ("#FF5555", "#3F1515"),
+            ("#FF55FF", "#3F153F"),
+            ("#FFFF55", "#3F3F15"),
+            ("#FFFFFF", "#3F3F3F"),
+        ];
+        for (color, (hex, shadow)) in NamedColor::ALL.into_iter().zip(expected) {
+            let named = TextColor::Named(color);
+            assert_eq!(named.to_hex(), hex);
+            assert_eq!(TextColor::from(color.shadow_rgb()).to_hex(), shadow);
+            assert_eq!(named.shadow_rgb(), color.shadow_rgb());
+        }
+    }
+
     #[test]
     fn test_text_color_hex() {
         assert_eq!(
             TextColor::from_hex("#FF5555"),
-            Some(TextColor::Rgb {
+            Ok(TextColor::Rgb {
                 r: 255,
                 g: 85,
                 b: 85
             })
         );
+        assert_eq!(
+            TextColor::from_hex("f0a"),
+            Ok(TextColor::Rgb {
+                r: 255,
+                g: 0,
+                b: 170
+            })
+        );
+        assert!(TextColor::from_hex("#FF55").is_err());
+        assert!(TextColor::from_hex("#GG5555").is_err());
+        assert!(TextColor::from_hex("a\u{20ac}bc").is_err());
+        assert_eq!(
+            TextColor::from_hex_prefer_named("#ff5555"),
+            Ok(TextColor::Named(NamedColor::Red))
+        );
         assert_eq!(TextColor::Named(NamedColor::Red).to_hex(), "#FF5555");
     }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let black = TextColor::Named(NamedColor::Black);
+        let white = TextColor::Named(NamedColor::White);
+        assert_eq!(black.luminance(), 0.0);
+        assert!((white.luminance() - 1.0).abs() < 1e-4);
+        assert!((black.contrast_ratio(&white) - 21.0).abs() < 1e-3);
+        assert_eq!(black.contrast_ratio(&white), white.contrast_ratio(&black));
+        assert!(TextColor::Named(NamedColor::DarkGray).contrast_ratio(&black) < 4.5);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_text_color_serde() {
+        let named = TextColor::Named(NamedColor::DarkRed);
+        let rgb = TextColor::Rgb { r: 1, g: 2, b: 3 };
+        let json = serde_json::to_string(&[named, rgb]).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"named":"dark_red"},{"rgb":{"r":1,"g":2,"b":3}}]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<TextColor>>(&json).unwrap(),
+            vec![named, rgb]
+        );
+    }
+
+    #[test]
+    fn test_hsv_hsl_roundtrip() {
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(15) {
+                for b in (0..=255).step_by(15) {
+                    let color = TextColor::Rgb { r, g, b };
+                    let (h, s, v) = color.to_hsv();
+                    assert_eq!(TextColor::from_hsv(h, s, v), color);
+                    let (h, s, l) = color.to_hsl();
+                    assert_eq!(TextColor::from_hsl(h, s, l), color);
+                }
+            }
+        }
+        assert_eq!(TextColor::from((0, 0, 255)).to_hsv(), (240.0, 1.0, 1.0));
+        assert_eq!(TextColor::from((255, 0, 0)).to_hsl(), (0.0, 1.0, 0.5));
+        assert_eq!(
+            TextColor::from((255, 0, 0)).lighten(0.25).rgb(),
+            (255, 128, 128)
+        );
+        assert_eq!(TextColor::from((255, 0, 0)).darken(1.0).rgb(), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_lerp() {
+        let black = TextColor::Named(NamedColor::Black);
+        let white = TextColor::Rgb {
+            r: 255,
+            g: 255,
+            b: 255,
+        };
+        assert_eq!(TextColor::lerp(&black, &white, 0.5).rgb(), (128, 128, 128));
+        assert_eq!(TextColor::lerp(&black, &white, -1.0).rgb(), (0, 0, 0));
+        assert_eq!(TextColor::lerp(&black, &white, 2.0), white);
+        assert_eq!(
+            TextColor::lerp(&black, &TextColor::Named(NamedColor::Gold), 1.0),
+            TextColor::Rgb {
+                r: 255,
+                g: 170,
+                b: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_nearest_named() {
+        for color in NamedColor::ALL {
+            assert_eq!(TextColor::from(color.rgb()).nearest_named(), color);
+        }
+        assert_eq!(
+            TextColor::Rgb {
+                r: 250,
+                g: 80,
+                b: 90
+            }
+            .nearest_named(),
+            NamedColor::Red
+        );
+        assert_eq!(
+            TextColor::Named(NamedColor::Gold).nearest_named(),
+            NamedColor::Gold
+        );
+    }
 }
@@ -0,0 +1,77 @@
+use crate::text::MCText;
+use alloc::string::String;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ClickEvent {
+    OpenUrl(String),
+    RunCommand(String),
+    SuggestCommand(String),
+    CopyToClipboard(String),
+    ChangePage(String),
+}
+
+impl ClickEvent {
+    pub fn from_action(action: &str, value: impl Into<String>) -> Option<ClickEvent> {
+        let value = value.into();
+        match action {
+            "open_url" => Some(ClickEvent::OpenUrl(value)),
+            "run_command" => Some(ClickEvent::RunCommand(value)),
+            "suggest_command" => Some(ClickEvent::SuggestCommand(value)),
+            "copy_to_clipboard" => Some(ClickEvent::CopyToClipboard(value)),
+            "change_page" => Some(ClickEvent::ChangePage(value)),
+            _ => None,
+        }
+    }
+
+    pub fn action(&self) -> &'static str {
+        match self {
+            ClickEvent::OpenUrl(_) => "open_url",
+            ClickEvent::RunCommand(_) => "run_command",
+            ClickEvent::SuggestCommand(_) => "suggest_command",
+            ClickEvent::CopyToClipboard(_) => "copy_to_clipboard",
+            ClickEvent::ChangePage(_) => "change_page",
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        match self {
+            ClickEvent::OpenUrl(v)
+            | ClickEvent::RunCommand(v)
+            | ClickEvent::SuggestCommand(v)
+            | ClickEvent::CopyToClipboard(v)
+            | ClickEvent::ChangePage(v) => v,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HoverEvent {
+    ShowText(MCText),
+    ShowItem {
+        id: String,
+        count: Option<i32>,
+        tag: Option<String>,
+    },
+    ShowEntity {
+        entity_type: String,
+        id: String,
+        name: Option<MCText>,
+    },
+}
+
+impl HoverEvent {
+    pub fn action(&self) -> &'static str {
+        match self {
+            HoverEvent::ShowText(_) => "show_text",
+            HoverEvent::ShowItem { .. } => "show_item",
+            HoverEvent::ShowEntity { .. } => "show_entity",
+        }
+    }
+}
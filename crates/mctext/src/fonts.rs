@@ -43,6 +43,27 @@ impl FontFamily {
         }
     }
 
+    pub fn from_resource(name: &str) -> Option<FontFamily> {
+        match name.strip_prefix("minecraft:").unwrap_or(name) {
+            "default" => Some(FontFamily::Minecraft),
+            #[cfg(feature = "special-fonts")]
+            "alt" => Some(FontFamily::Enchanting),
+            #[cfg(feature = "special-fonts")]
+            "illageralt" => Some(FontFamily::Illager),
+            _ => None,
+        }
+    }
+
+    pub fn resource(&self) -> &'static str {
+        match self {
+            FontFamily::Minecraft => "minecraft:default",
+            #[cfg(feature = "special-fonts")]
+            FontFamily::Enchanting => "minecraft:alt",
+            #[cfg(feature = "special-fonts")]
+            FontFamily::Illager => "minecraft:illageralt",
+        }
+    }
+
     pub fn supports_styles(&self) -> bool {
         matches!(self, FontFamily::Minecraft)
     }
@@ -90,6 +111,10 @@ impl FontVariant {
         }
     }
 
+    pub fn is_bold(&self) -> bool {
+        matches!(self, FontVariant::Bold | FontVariant::BoldItalic)
+    }
+
     #[cfg(feature = "modern-fonts")]
     pub fn data(&self) -> &'static [u8] {
         self.data_for_version(FontVersion::Modern)
@@ -1,36 +1,271 @@
 use crate::color::TextColor;
-use crate::style::Style;
-use crate::text::{MCText, Span};
+use crate::event::{ClickEvent, HoverEvent};
+use crate::style::{OptStyle, Style};
+use crate::text::{ComponentContent, MCText, Span, Translation};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 use serde_json::{Map, Value};
-use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
-    InvalidJson(String),
+    InvalidJson {
+        message: String,
+        line: usize,
+        column: usize,
+        at: usize,
+    },
+    InvalidMiniMessage {
+        message: String,
+        at: usize,
+    },
+    InvalidColor(String),
+    InvalidHex(String),
+    InvalidSnbt {
+        message: String,
+        at: usize,
+    },
+    UnknownField {
+        name: String,
+        path: String,
+    },
+    InvalidField {
+        name: String,
+        path: String,
+        expected: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    Strict,
+    #[default]
+    Lenient,
+}
+
+impl ParseError {
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ParseError::InvalidJson { at, .. }
+            | ParseError::InvalidMiniMessage { at, .. }
+            | ParseError::InvalidSnbt { at, .. } => Some(*at),
+            ParseError::InvalidColor(_)
+            | ParseError::InvalidHex(_)
+            | ParseError::UnknownField { .. }
+            | ParseError::InvalidField { .. } => None,
+        }
+    }
+
+    fn from_json(json: &str, error: serde_json::Error) -> Self {
+        let message = error.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(index) => message[..index].to_string(),
+            None => message,
+        };
+        let (line, column) = (error.line(), error.column());
+        let at = json
+            .split_inclusive('\n')
+            .take(line.saturating_sub(1))
+            .map(str::len)
+            .sum::<usize>()
+            + column.saturating_sub(1);
+        ParseError::InvalidJson {
+            message,
+            line,
+            column,
+            at: at.min(json.len()),
+        }
+    }
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ParseError::InvalidJson(msg) => write!(f, "invalid JSON: {}", msg),
+            ParseError::InvalidJson {
+                message,
+                line,
+                column,
+                ..
+            } => write!(
+                f,
+                "invalid JSON at line {} column {}: {}",
+                line, column, message
+            ),
+            ParseError::InvalidMiniMessage { message, at } => {
+                write!(f, "invalid MiniMessage at byte {}: {}", at, message)
+            }
+            ParseError::InvalidColor(name) => write!(f, "invalid color: {}", name),
+            ParseError::InvalidHex(hex) => write!(f, "invalid hex color: {}", hex),
+            ParseError::InvalidSnbt { message, at } => {
+                write!(f, "invalid SNBT at byte {}: {}", at, message)
+            }
+            ParseError::UnknownField { name, path } => {
+                write!(f, "unknown field \"{}\" at {}", name, path)
+            }
+            ParseError::InvalidField {
+                name,
+                path,
+                expected,
+            } => write!(f, "field \"{}\" at {} must be {}", name, path, expected),
         }
     }
 }
 
-impl std::error::Error for ParseError {}
+impl core::error::Error for ParseError {}
 
 pub fn try_parse_json_component(json: &str) -> Result<MCText, ParseError> {
-    let value =
-        serde_json::from_str::<Value>(json).map_err(|e| ParseError::InvalidJson(e.to_string()))?;
+    try_parse_json_component_with(json, ParseMode::Lenient)
+}
+
+pub fn try_parse_json_component_with(json: &str, mode: ParseMode) -> Result<MCText, ParseError> {
+    let value = serde_json::from_str::<Value>(json).map_err(|e| ParseError::from_json(json, e))?;
+    if mode == ParseMode::Strict {
+        validate_component(&value, "$")?;
+    }
     Ok(parse_value(&value))
 }
 
-fn parse_value(value: &Value) -> MCText {
+const STYLE_FIELDS: [&str; 5] = [
+    "bold",
+    "italic",
+    "underlined",
+    "strikethrough",
+    "obfuscated",
+];
+const STRING_FIELDS: [&str; 12] = [
+    "type",
+    "text",
+    "translate",
+    "fallback",
+    "font",
+    "insertion",
+    "keybind",
+    "selector",
+    "nbt",
+    "block",
+    "entity",
+    "storage",
+];
+
+fn validate_component(value: &Value, path: &str) -> Result<(), ParseError> {
+    let invalid = |name: &str, expected| ParseError::InvalidField {
+        name: name.to_string(),
+        path: path.to_string(),
+        expected,
+    };
+
+    let obj = match value {
+        Value::String(_) => return Ok(()),
+        Value::Array(items) if !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                validate_component(item, &format!("{}[{}]", path, i))?;
+            }
+            return Ok(());
+        }
+        Value::Object(obj) => obj,
+        _ => return Err(invalid("component", "a string, object or non-empty array")),
+    };
+
+    for (name, field) in obj {
+        let name = name.as_str();
+        match name {
+            _ if STRING_FIELDS.contains(&name) => {
+                if !field.is_string() {
+                    return Err(invalid(name, "a string"));
+                }
+            }
+            _ if STYLE_FIELDS.contains(&name) => {
+                if !field.is_boolean() {
+                    return Err(invalid(name, "a boolean"));
+                }
+            }
+            "color" => {
+                let color = field.as_str().ok_or_else(|| invalid(name, "a string"))?;
+                if TextColor::parse(color).is_none() {
+                    return Err(ParseError::InvalidColor(color.to_string()));
+                }
+            }
+            "interpret" => {
+                if !field.is_boolean() {
+                    return Err(invalid(name, "a boolean"));
+                }
+            }
+            "extra" | "with" => {
+                let items = field.as_array().ok_or_else(|| invalid(name, "an array"))?;
+                for (i, item) in items.iter().enumerate() {
+                    validate_component(item, &format!("{}.{}[{}]", path, name, i))?;
+                }
+            }
+            "separator" => validate_component(field, &format!("{}.{}", path, name))?,
+            "score" => {
+                let score = field
+                    .as_object()
+                    .ok_or_else(|| invalid(name, "an object"))?;
+                if !score.get("name").is_some_and(Value::is_string)
+                    || !score.get("objective").is_some_and(Value::is_string)
+                {
+                    return Err(invalid(name, "an object with name and objective"));
+                }
+            }
+            "clickEvent" | "hoverEvent" => {
+                let event = field
+                    .as_object()
+                    .ok_or_else(|| invalid(name, "an object"))?;
+                if !event.get("action").is_some_and(Value::is_string) {
+                    return Err(invalid(name, "an object with a string action"));
+                }
+            }
+            "shadow_color" => {
+                if !field.is_number() && !field.is_array() {
+                    return Err(invalid(name, "a number or an array"));
+                }
+            }
+            _ => {
+                return Err(ParseError::UnknownField {
+                    name: name.to_string(),
+                    path: path.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn parse_value(value: &Value) -> MCText {
     let mut text = MCText::new();
-    extract_spans(value, None, Style::default(), &mut text);
+    extract_spans(value, &Inherited::default(), &mut text);
     text
 }
 
+#[derive(Clone, Default)]
+struct Inherited {
+    color: Option<TextColor>,
+    style: Style,
+    click_event: Option<ClickEvent>,
+    hover_event: Option<HoverEvent>,
+    font: Option<String>,
+    insertion: Option<String>,
+}
+
+impl Inherited {
+    fn span(&self, text: impl Into<String>) -> Span {
+        Span {
+            text: text.into(),
+            color: self.color,
+            style: self.style,
+            click_event: self.click_event.clone(),
+            hover_event: self.hover_event.clone(),
+            translate: None,
+            content: None,
+            font: self.font.clone(),
+            insertion: self.insertion.clone(),
+        }
+    }
+}
+
 fn extract_color(obj: &Map<String, Value>, fallback: Option<TextColor>) -> Option<TextColor> {
     obj.get("color")
         .and_then(|v| v.as_str())
@@ -38,100 +273,193 @@ fn extract_color(obj: &Map<String, Value>, fallback: Option<TextColor>) -> Optio
         .or(fallback)
 }
 
-fn extract_style(obj: &Map<String, Value>, parent: &Style) -> Style {
-    let get_bool = |key: &str, default: bool| -> bool {
-        obj.get(key).and_then(|v| v.as_bool()).unwrap_or(default)
+fn extract_style(obj: &Map<String, Value>) -> OptStyle {
+    let get_bool = |key: &str| -> Option<bool> {
+        obj.get(key)
+            .and_then(|v| v.as_bool().or_else(|| v.as_i64().map(|n| n != 0)))
     };
 
-    Style {
-        bold: get_bool("bold", parent.bold),
-        italic: get_bool("italic", parent.italic),
-        underlined: get_bool("underlined", parent.underlined),
-        strikethrough: get_bool("strikethrough", parent.strikethrough),
-        obfuscated: get_bool("obfuscated", parent.obfuscated),
+    OptStyle {
+        bold: get_bool("bold"),
+        italic: get_bool("italic"),
+        underlined: get_bool("underlined"),
+        strikethrough: get_bool("strikethrough"),
+        obfuscated: get_bool("obfuscated"),
     }
 }
 
-fn push_text_with_inheritance(
-    content: &str,
-    color: Option<TextColor>,
-    style: Style,
-    text: &mut MCText,
-) {
+fn extract_click_event(obj: &Map<String, Value>) -> Option<ClickEvent> {
+    let event = obj.get("clickEvent")?.as_object()?;
+    let action = event.get("action")?.as_str()?;
+    let value = match event.get("value")? {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    ClickEvent::from_action(action, value)
+}
+
+fn extract_hover_event(obj: &Map<String, Value>) -> Option<HoverEvent> {
+    let event = obj.get("hoverEvent")?.as_object()?;
+    let action = event.get("action")?.as_str()?;
+    let contents = event.get("contents").or_else(|| event.get("value"))?;
+
+    match action {
+        "show_text" => Some(HoverEvent::ShowText(parse_value(contents))),
+        "show_item" => match contents {
+            Value::String(id) => Some(HoverEvent::ShowItem {
+                id: id.clone(),
+                count: None,
+                tag: None,
+            }),
+            Value::Object(item) => Some(HoverEvent::ShowItem {
+                id: item.get("id")?.as_str()?.to_string(),
+                count: item.get("count").and_then(|v| v.as_i64()).map(|c| c as i32),
+                tag: item.get("tag").and_then(|v| v.as_str()).map(String::from),
+            }),
+            _ => None,
+        },
+        "show_entity" => {
+            let entity = contents.as_object()?;
+            Some(HoverEvent::ShowEntity {
+                entity_type: entity.get("type")?.as_str()?.to_string(),
+                id: entity.get("id")?.as_str()?.to_string(),
+                name: entity.get("name").map(parse_value),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn push_text_with_inheritance(content: &str, inherited: &Inherited, text: &mut MCText) {
     if content.is_empty() {
         return;
     }
 
     let parsed = MCText::parse(content);
     if parsed.is_empty() {
-        text.push(Span {
-            text: content.to_string(),
-            color,
-            style,
-        });
+        text.push(inherited.span(content));
         return;
     }
 
     let has_color_codes = parsed.spans().iter().any(|s| s.color.is_some());
     if !has_color_codes {
-        text.push(Span {
-            text: content.to_string(),
-            color,
-            style,
-        });
+        text.push(inherited.span(content));
         return;
     }
 
-    for mut span in parsed.into_spans() {
-        if span.color.is_none() {
-            span.color = color;
+    for span in parsed.into_spans() {
+        let mut merged = inherited.span(span.text);
+        if span.color.is_some() {
+            merged.color = span.color;
         }
-        span.style = span.style.merge(&style);
-        text.push(span);
+        merged.style = span.style.merge(&inherited.style);
+        text.push(merged);
     }
 }
 
-fn extract_spans(
-    value: &Value,
-    parent_color: Option<TextColor>,
-    parent_style: Style,
-    text: &mut MCText,
-) {
+fn extract_spans(value: &Value, parent: &Inherited, text: &mut MCText) {
     match value {
         Value::String(s) => {
-            push_text_with_inheritance(s, parent_color, parent_style, text);
+            push_text_with_inheritance(s, parent, text);
         }
         Value::Object(obj) => {
-            let color = extract_color(obj, parent_color);
-            let style = extract_style(obj, &parent_style);
+            let inherited = extract_inherited(obj, parent);
 
             if let Some(t) = obj.get("text").and_then(|v| v.as_str()) {
-                push_text_with_inheritance(t, color, style, text);
+                push_text_with_inheritance(t, &inherited, text);
             }
 
-            if let Some(translate) = obj.get("translate").and_then(|v| v.as_str()) {
-                text.push(Span {
-                    text: translate.to_string(),
-                    color,
-                    style,
+            if let Some(key) = obj.get("translate").and_then(|v| v.as_str()) {
+                let fallback = obj
+                    .get("fallback")
+                    .and_then(|v| v.as_str())
+                    .map(String::from);
+                let with = obj
+                    .get("with")
+                    .and_then(|v| v.as_array())
+                    .map(|args| args.iter().map(parse_value).collect())
+                    .unwrap_or_default();
+                let mut span = inherited.span(fallback.as_deref().unwrap_or(key));
+                span.translate = Some(Translation {
+                    key: key.to_string(),
+                    with,
+                    fallback,
                 });
+                text.push(span);
+            }
+
+            if let Some(content) = extract_content(obj) {
+                let mut span = inherited.span(content.placeholder());
+                span.content = Some(content);
+                text.push(span);
             }
 
             if let Some(extra) = obj.get("extra").and_then(|v| v.as_array()) {
                 for item in extra {
-                    extract_spans(item, color, style, text);
+                    extract_spans(item, &inherited, text);
                 }
             }
         }
         Value::Array(arr) => {
-            for item in arr {
-                extract_spans(item, parent_color, parent_style, text);
+            let Some((first, rest)) = arr.split_first() else {
+                return;
+            };
+            extract_spans(first, parent, text);
+
+            let inherited = match first {
+                Value::Object(obj) => extract_inherited(obj, parent),
+                _ => parent.clone(),
+            };
+            for item in rest {
+                extract_spans(item, &inherited, text);
             }
         }
         _ => {}
     }
 }
 
+fn extract_content(obj: &Map<String, Value>) -> Option<ComponentContent> {
+    if let Some(score) = obj.get("score").and_then(|v| v.as_object()) {
+        return Some(ComponentContent::Score {
+            name: score.get("name")?.as_str()?.to_string(),
+            objective: score.get("objective")?.as_str()?.to_string(),
+            value: score
+                .get("value")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+        });
+    }
+    if let Some(selector) = obj.get("selector").and_then(|v| v.as_str()) {
+        return Some(ComponentContent::Selector {
+            selector: selector.to_string(),
+            separator: obj.get("separator").map(parse_value),
+        });
+    }
+    obj.get("keybind")
+        .and_then(|v| v.as_str())
+        .map(|key| ComponentContent::Keybind(key.to_string()))
+}
+
+fn extract_inherited(obj: &Map<String, Value>, parent: &Inherited) -> Inherited {
+    Inherited {
+        color: extract_color(obj, parent.color),
+        style: extract_style(obj).apply(&parent.style),
+        click_event: extract_click_event(obj).or_else(|| parent.click_event.clone()),
+        hover_event: extract_hover_event(obj).or_else(|| parent.hover_event.clone()),
+        font: obj
+            .get("font")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| parent.font.clone()),
+        insertion: obj
+            .get("insertion")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| parent.insertion.clone()),
+    }
+}
+
 pub fn to_json(text: &MCText) -> String {
     if text.spans().is_empty() {
         return r#"{"text":""}"#.to_string();
@@ -152,15 +480,205 @@ pub fn to_json(text: &MCText) -> String {
     format!("[{}]", components.join(","))
 }
 
+pub fn to_json_compact(text: &MCText) -> String {
+    let spans = text.spans();
+    let Some(first) = spans.first().filter(|_| spans.len() > 1) else {
+        return to_json(text);
+    };
+
+    let all = |f: fn(&Span) -> bool| spans.iter().all(f);
+    let same = |f: fn(&Span, &Span) -> bool| spans.iter().all(|s| f(s, first));
+    let shared = Span {
+        color: first.color.filter(|_| same(|a, b| a.color == b.color)),
+        style: Style {
+            bold: all(|s| s.style.bold),
+            italic: all(|s| s.style.italic),
+            underlined: all(|s| s.style.underlined),
+            strikethrough: all(|s| s.style.strikethrough),
+            obfuscated: all(|s| s.style.obfuscated),
+        },
+        font: first.font.clone().filter(|_| same(|a, b| a.font == b.font)),
+        insertion: first
+            .insertion
+            .clone()
+            .filter(|_| same(|a, b| a.insertion == b.insertion)),
+        click_event: first
+            .click_event
+            .clone()
+            .filter(|_| same(|a, b| a.click_event == b.click_event)),
+        hover_event: first
+            .hover_event
+            .clone()
+            .filter(|_| same(|a, b| a.hover_event == b.hover_event)),
+        ..Default::default()
+    };
+
+    let stripped: Vec<Span> = spans
+        .iter()
+        .map(|span| Span {
+            color: span.color.filter(|_| shared.color.is_none()),
+            font: span.font.clone().filter(|_| shared.font.is_none()),
+            insertion: span
+                .insertion
+                .clone()
+                .filter(|_| shared.insertion.is_none()),
+            click_event: span
+                .click_event
+                .clone()
+                .filter(|_| shared.click_event.is_none()),
+            hover_event: span
+                .hover_event
+                .clone()
+                .filter(|_| shared.hover_event.is_none()),
+            ..span.clone()
+        })
+        .collect();
+
+    let children = stripped
+        .chunk_by(|a, b| a.color.is_some() && a.color == b.color)
+        .map(|run| match run {
+            [span] => span_to_json_within(span, &shared.style),
+            _ => {
+                let head = Span {
+                    color: run[0].color,
+                    ..Default::default()
+                };
+                let members = run
+                    .iter()
+                    .map(|span| {
+                        span_to_json_within(
+                            &Span {
+                                color: None,
+                                ..span.clone()
+                            },
+                            &shared.style,
+                        )
+                    })
+                    .collect();
+                with_extra(&head, members)
+            }
+        })
+        .collect();
+
+    with_extra(&shared, children)
+}
+
+fn with_extra(head: &Span, children: Vec<String>) -> String {
+    let head = span_to_json(head);
+    format!(
+        r#"{},"extra":[{}]}}"#,
+        &head[..head.len() - 1],
+        children.join(",")
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            ch if ch.is_control() => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn click_event_to_json(event: &ClickEvent) -> String {
+    format!(
+        r#"{{"action":"{}","value":"{}"}}"#,
+        event.action(),
+        escape(event.value())
+    )
+}
+
+fn hover_event_to_json(event: &HoverEvent) -> String {
+    let contents = match event {
+        HoverEvent::ShowText(text) => to_json(text),
+        HoverEvent::ShowItem { id, count, tag } => {
+            let mut parts = vec![format!(r#""id":"{}""#, escape(id))];
+            if let Some(count) = count {
+                parts.push(format!(r#""count":{}"#, count));
+            }
+            if let Some(tag) = tag {
+                parts.push(format!(r#""tag":"{}""#, escape(tag)));
+            }
+            format!("{{{}}}", parts.join(","))
+        }
+        HoverEvent::ShowEntity {
+            entity_type,
+            id,
+            name,
+        } => {
+            let mut parts = vec![
+                format!(r#""type":"{}""#, escape(entity_type)),
+                format!(r#""id":"{}""#, escape(id)),
+            ];
+            if let Some(name) = name {
+                parts.push(format!(r#""name":{}"#, to_json(name)));
+            }
+            format!("{{{}}}", parts.join(","))
+        }
+    };
+    format!(
+        r#"{{"action":"{}","contents":{}}}"#,
+        event.action(),
+        contents
+    )
+}
+
 fn span_to_json(span: &Span) -> String {
+    span_to_json_within(span, &Style::default())
+}
+
+fn span_to_json_within(span: &Span, inherited: &Style) -> String {
     let mut parts = Vec::new();
 
-    let escaped_text = span
-        .text
-        .replace('\\', "\\\\")
-        .replace('"', "\\\"")
-        .replace('\n', "\\n");
-    parts.push(format!(r#""text":"{}""#, escaped_text));
+    match &span.translate {
+        Some(translation) => {
+            parts.push(format!(r#""translate":"{}""#, escape(&translation.key)));
+            if let Some(fallback) = &translation.fallback {
+                parts.push(format!(r#""fallback":"{}""#, escape(fallback)));
+            }
+            if !translation.with.is_empty() {
+                let args: Vec<String> = translation.with.iter().map(to_json).collect();
+                parts.push(format!(r#""with":[{}]"#, args.join(",")));
+            }
+        }
+        None => match &span.content {
+            Some(ComponentContent::Score {
+                name,
+                objective,
+                value,
+            }) => {
+                let mut score = vec![
+                    format!(r#""name":"{}""#, escape(name)),
+                    format!(r#""objective":"{}""#, escape(objective)),
+                ];
+                if let Some(value) = value {
+                    score.push(format!(r#""value":"{}""#, escape(value)));
+                }
+                parts.push(format!(r#""score":{{{}}}"#, score.join(",")));
+            }
+            Some(ComponentContent::Selector {
+                selector,
+                separator,
+            }) => {
+                parts.push(format!(r#""selector":"{}""#, escape(selector)));
+                if let Some(separator) = separator {
+                    parts.push(format!(r#""separator":{}"#, to_json(separator)));
+                }
+            }
+            Some(ComponentContent::Keybind(key)) => {
+                parts.push(format!(r#""keybind":"{}""#, escape(key)));
+            }
+            None => parts.push(format!(r#""text":"{}""#, escape(&span.text))),
+        },
+    }
 
     if let Some(color) = span.color {
         let color_str = match color {
@@ -170,20 +688,35 @@ fn span_to_json(span: &Span) -> String {
         parts.push(format!(r#""color":"{}""#, color_str));
     }
 
-    if span.style.bold {
-        parts.push(r#""bold":true"#.to_string());
+    if let Some(font) = &span.font {
+        parts.push(format!(r#""font":"{}""#, escape(font)));
     }
-    if span.style.italic {
-        parts.push(r#""italic":true"#.to_string());
+
+    let flags = [
+        ("bold", span.style.bold, inherited.bold),
+        ("italic", span.style.italic, inherited.italic),
+        ("underlined", span.style.underlined, inherited.underlined),
+        (
+            "strikethrough",
+            span.style.strikethrough,
+            inherited.strikethrough,
+        ),
+        ("obfuscated", span.style.obfuscated, inherited.obfuscated),
+    ];
+    for (key, value, parent) in flags {
+        if value != parent {
+            parts.push(format!(r#""{}":{}"#, key, value));
+        }
     }
-    if span.style.underlined {
-        parts.push(r#""underlined":true"#.to_string());
+
+    if let Some(insertion) = &span.insertion {
+        parts.push(format!(r#""insertion":"{}""#, escape(insertion)));
     }
-    if span.style.strikethrough {
-        parts.push(r#""strikethrough":true"#.to_string());
+    if let Some(event) = &span.click_event {
+        parts.push(format!(r#""clickEvent":{}"#, click_event_to_json(event)));
     }
-    if span.style.obfuscated {
-        parts.push(r#""obfuscated":true"#.to_string());
+    if let Some(event) = &span.hover_event {
+        parts.push(format!(r#""hoverEvent":{}"#, hover_event_to_json(event)));
     }
 
     format!("{{{}}}", parts.join(","))
@@ -202,11 +735,178 @@ mod tests {
         assert_eq!(text.spans().len(), 2);
     }
 
+    #[test]
+    fn test_parse_json_primitives() {
+        let text = try_parse_json_component("\"just text\"").unwrap();
+        assert_eq!(text.plain_text(), "just text");
+        assert_eq!(text.spans()[0].color, None);
+
+        let text = try_parse_json_component(r#"["a",{"text":"b","bold":true}]"#).unwrap();
+        assert_eq!(text.plain_text(), "ab");
+        assert!(!text.spans()[0].style.bold);
+        assert!(text.spans()[1].style.bold);
+
+        let text = try_parse_json_component(r#"[{"text":"a","color":"red"},"b"]"#).unwrap();
+        assert!(
+            text.spans()
+                .iter()
+                .all(|span| span.color == Some(TextColor::Named(NamedColor::Red)))
+        );
+    }
+
+    #[test]
+    fn test_nested_style_inheritance() {
+        let text = try_parse_json_component(
+            r#"{"text":"a","bold":true,"extra":[{"text":"b","italic":true},{"text":"c","bold":false}]}"#,
+        )
+        .unwrap();
+        let spans = text.spans();
+        assert!(spans[0].style.bold);
+        assert!(spans[1].style.bold && spans[1].style.italic);
+        assert!(!spans[2].style.bold);
+
+        let json = r#"{"text":"a","bold":true,"extra":[{"text":"x"}]}"#;
+        let text = try_parse_json_component(json).unwrap();
+        assert!(text.spans().iter().all(|span| span.style.bold));
+        assert_eq!(
+            to_json_compact(&text),
+            r#"{"text":"","bold":true,"extra":[{"text":"a"},{"text":"x"}]}"#
+        );
+        assert_eq!(
+            span_to_json_within(&Span::new("y"), &Style::default().bold()),
+            r#"{"text":"y","bold":false}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_json_error_position() {
+        let error = try_parse_json_component("{\n  \"text\": \"a\",,\n}").unwrap_err();
+
+        assert!(matches!(
+            error,
+            ParseError::InvalidJson {
+                line: 2,
+                column: 15,
+                ..
+            }
+        ));
+        assert_eq!(error.offset(), Some(16));
+        assert!(
+            error
+                .to_string()
+                .starts_with("invalid JSON at line 2 column 15")
+        );
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        let typo = r#"{"text":"hi","colour":"red"}"#;
+        assert!(try_parse_json_component(typo).is_ok());
+        assert_eq!(
+            try_parse_json_component_with(typo, ParseMode::Strict),
+            Err(ParseError::UnknownField {
+                name: "colour".to_string(),
+                path: "$".to_string(),
+            })
+        );
+
+        let wrong_type = r#"{"text":"a","extra":[{"text":"b","bold":"yes"}]}"#;
+        assert!(matches!(
+            try_parse_json_component_with(wrong_type, ParseMode::Strict),
+            Err(ParseError::InvalidField { ref path, .. }) if path == "$.extra[0]"
+        ));
+
+        let valid = r##"["",{"text":"a","color":"#ff0000","bold":true,"clickEvent":{"action":"open_url","value":"https://x"}}]"##;
+        assert!(try_parse_json_component_with(valid, ParseMode::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_special_content_roundtrip() {
+        let json = r#"[{"text":""},{"score":{"name":"@p","objective":"kills"},"color":"gold"},{"selector":"@a","separator":{"text":", "}},{"keybind":"key.jump"}]"#;
+        let text = try_parse_json_component(json).unwrap();
+
+        assert_eq!(text.plain_text(), "@p@akey.jump");
+        assert_eq!(to_json(&text), json);
+        assert_eq!(
+            text.spans()[2].content,
+            Some(ComponentContent::Keybind("key.jump".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_events_roundtrip() {
+        let json = r#"{"text":"Click","clickEvent":{"action":"run_command","value":"/spawn"},"hoverEvent":{"action":"show_text","contents":{"text":"Go home","color":"gold"}}}"#;
+        let text = try_parse_json_component(json).unwrap();
+        let span = &text.spans()[0];
+        assert_eq!(
+            span.click_event,
+            Some(ClickEvent::RunCommand("/spawn".to_string()))
+        );
+        assert!(
+            matches!(&span.hover_event, Some(HoverEvent::ShowText(t)) if t.plain_text() == "Go home")
+        );
+        assert_eq!(text.plain_text(), "Click");
+        assert_eq!(text.to_legacy(), "Click");
+        assert_eq!(to_json(&text), json);
+    }
+
+    #[test]
+    fn test_translate_roundtrip() {
+        let json = r#"{"translate":"death.attack.arrow","with":[{"text":"Steve"},{"text":"Skeleton"}],"color":"red"}"#;
+        let text = try_parse_json_component(json).unwrap();
+        let translation = text.spans()[0].translate.as_ref().unwrap();
+        assert_eq!(translation.key, "death.attack.arrow");
+        assert_eq!(translation.with.len(), 2);
+        assert_eq!(text.plain_text(), "death.attack.arrow");
+        assert_eq!(to_json(&text), json);
+    }
+
+    #[test]
+    fn test_font_roundtrip() {
+        let json = r#"[{"text":""},{"text":"rune","color":"aqua","font":"minecraft:alt"},{"text":"odd","font":"custom:thing"}]"#;
+        let text = try_parse_json_component(json).unwrap();
+        let spans = text.spans();
+        assert_eq!(spans[0].font.as_deref(), Some("minecraft:alt"));
+        assert_eq!(spans[1].font.as_deref(), Some("custom:thing"));
+        assert_eq!(spans[1].font_family(), None);
+        assert_eq!(to_json(&text), json);
+    }
+
+    #[test]
+    fn test_insertion_roundtrip() {
+        let json = r#"{"text":"Steve","insertion":"/msg Steve "}"#;
+        let text = try_parse_json_component(json).unwrap();
+        assert_eq!(text.spans()[0].insertion.as_deref(), Some("/msg Steve "));
+        assert_eq!(text.plain_text(), "Steve");
+        assert_eq!(text.to_legacy(), "Steve");
+        assert_eq!(to_json(&text), json);
+    }
+
+    #[test]
+    fn test_to_json_compact() {
+        let text =
+            MCText::parse("\u{00A7}lA\u{00A7}c\u{00A7}lB\u{00A7}c\u{00A7}lC\u{00A7}9\u{00A7}lD");
+        let json = to_json_compact(&text);
+        assert_eq!(
+            json,
+            r#"{"text":"","bold":true,"extra":[{"text":"A"},{"text":"","color":"red","extra":[{"text":"B"},{"text":"C"}]},{"text":"D","color":"blue"}]}"#
+        );
+        assert_eq!(try_parse_json_component(&json).unwrap(), text);
+    }
+
     #[test]
     fn test_to_json() {
         let mut text = MCText::new();
         text.push(Span::new("Hello").with_color(NamedColor::Gold));
         let json = to_json(&text);
         assert!(json.contains("gold") && json.contains("Hello"));
+
+        let text = MCText::parse("a\tb\u{1}c\r");
+        let json = to_json(&text);
+        assert!(serde_json::from_str::<Value>(&json).is_ok());
+        assert_eq!(
+            try_parse_json_component(&json).unwrap().plain_text(),
+            "a\tb\u{1}c\r"
+        );
     }
 }
@@ -1,9 +1,14 @@
-use crate::color::TextColor;
-use crate::fonts::FontVariant;
+use crate::color::{SHADOW_OFFSET, TextColor};
+use crate::fonts::{FontFamily, FontVariant};
+use crate::render::RenderError;
 use crate::system::FontSystem;
-use crate::text::MCText;
+use crate::text::{MCText, OBFUSCATION_POOL, next_random};
+use crate::wrap::{self, Token};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 const SHADOW_OFFSET_RATIO: f32 = 1.0 / 12.0;
+const MIN_SIZE: f32 = 1.0;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum TextAlign {
@@ -13,13 +18,41 @@ pub enum TextAlign {
     Right,
 }
 
+fn is_positive(value: f32) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerticalAnchor {
+    #[default]
+    Top,
+    Baseline,
+    Middle,
+    Bottom,
+}
+
 #[derive(Debug, Clone)]
 pub struct LayoutOptions {
     pub size: f32,
     pub max_width: Option<f32>,
     pub align: TextAlign,
+    pub vertical_anchor: VerticalAnchor,
     pub shadow: bool,
     pub line_spacing: f32,
+    pub letter_spacing: f32,
+    pub line_height: Option<f32>,
+    pub tab_width: Option<f32>,
+    pub background: Option<(TextColor, f32)>,
+    pub background_alpha: u8,
+    pub obfuscation_seed: Option<u64>,
+    pub custom_font: Option<String>,
+    pub force_variant: Option<FontVariant>,
+    pub force_family: Option<FontFamily>,
+    pub pixel_scale: Option<u32>,
+    pub kerning: bool,
+    pub shadow_color: Option<TextColor>,
+    pub shadow_offset: Option<(f32, f32)>,
+    pub outline: Option<(TextColor, f32)>,
 }
 
 impl Default for LayoutOptions {
@@ -28,8 +61,23 @@ impl Default for LayoutOptions {
             size: 16.0,
             max_width: None,
             align: TextAlign::Left,
+            vertical_anchor: VerticalAnchor::Top,
             shadow: true,
             line_spacing: -1.0,
+            letter_spacing: 0.0,
+            line_height: None,
+            tab_width: None,
+            background: None,
+            background_alpha: 255,
+            obfuscation_seed: None,
+            custom_font: None,
+            force_variant: None,
+            force_family: None,
+            pixel_scale: None,
+            kerning: true,
+            shadow_color: None,
+            shadow_offset: None,
+            outline: None,
         }
     }
 }
@@ -37,21 +85,42 @@ impl Default for LayoutOptions {
 impl LayoutOptions {
     pub fn new(size: f32) -> Self {
         Self {
-            size,
+            size: if is_positive(size) { size } else { MIN_SIZE },
             ..Default::default()
         }
     }
 
+    pub fn try_new(size: f32) -> Result<Self, RenderError> {
+        if is_positive(size) {
+            Ok(Self::new(size))
+        } else {
+            Err(RenderError::InvalidSize)
+        }
+    }
+
     pub fn with_max_width(mut self, width: f32) -> Self {
-        self.max_width = Some(width);
+        self.max_width = is_positive(width).then_some(width);
         self
     }
 
+    pub fn try_with_max_width(self, width: f32) -> Result<Self, RenderError> {
+        if is_positive(width) {
+            Ok(self.with_max_width(width))
+        } else {
+            Err(RenderError::InvalidMaxWidth)
+        }
+    }
+
     pub fn with_align(mut self, align: TextAlign) -> Self {
         self.align = align;
         self
     }
 
+    pub fn with_vertical_anchor(mut self, anchor: VerticalAnchor) -> Self {
+        self.vertical_anchor = anchor;
+        self
+    }
+
     pub fn with_shadow(mut self, shadow: bool) -> Self {
         self.shadow = shadow;
         self
@@ -61,6 +130,97 @@ impl LayoutOptions {
         self.line_spacing = spacing;
         self
     }
+
+    pub fn with_letter_spacing(mut self, spacing: f32) -> Self {
+        self.letter_spacing = if spacing.is_finite() { spacing } else { 0.0 };
+        self
+    }
+
+    pub fn with_line_height(mut self, multiple: f32) -> Self {
+        self.line_height = is_positive(multiple).then_some(multiple);
+        self
+    }
+
+    pub fn line_advance(&self) -> f32 {
+        match self.line_height {
+            Some(multiple) => self.size * multiple,
+            None => self.size + self.line_spacing,
+        }
+    }
+
+    pub fn with_tab_width(mut self, width: f32) -> Self {
+        self.tab_width = Some(width.max(0.0));
+        self
+    }
+
+    pub fn with_background(mut self, color: impl Into<TextColor>, padding: f32) -> Self {
+        self.background = Some((color.into(), padding.max(0.0)));
+        self
+    }
+
+    pub fn with_background_alpha(mut self, alpha: u8) -> Self {
+        self.background_alpha = alpha;
+        self
+    }
+
+    pub fn with_obfuscation_seed(mut self, seed: u64) -> Self {
+        self.obfuscation_seed = Some(seed);
+        self
+    }
+
+    pub fn with_custom_font(mut self, name: impl Into<String>) -> Self {
+        self.custom_font = Some(name.into());
+        self
+    }
+
+    pub fn with_force_variant(mut self, variant: FontVariant) -> Self {
+        self.force_variant = Some(variant);
+        self
+    }
+
+    pub fn with_force_family(mut self, family: FontFamily) -> Self {
+        self.force_family = Some(family);
+        self
+    }
+
+    pub fn with_pixel_scale(mut self, scale: u32) -> Self {
+        self.pixel_scale = Some(scale.max(1));
+        self
+    }
+
+    pub fn with_kerning(mut self, kerning: bool) -> Self {
+        self.kerning = kerning;
+        self
+    }
+
+    pub fn with_shadow_color(mut self, color: impl Into<TextColor>) -> Self {
+        self.shadow_color = Some(color.into());
+        self
+    }
+
+    pub fn with_shadow_offset(mut self, dx: f32, dy: f32) -> Self {
+        self.shadow_offset = Some((dx, dy));
+        self
+    }
+
+    pub fn with_outline(mut self, color: impl Into<TextColor>, width: f32) -> Self {
+        self.outline = is_positive(width).then(|| (color.into(), width));
+        self
+    }
+
+    pub fn shadow_offset(&self) -> (f32, f32) {
+        self.shadow_offset.unwrap_or_else(|| {
+            let offset = self.size * SHADOW_OFFSET_RATIO * SHADOW_OFFSET as f32;
+            (offset, offset)
+        })
+    }
+
+    fn shadow_color_for(&self, color: TextColor) -> TextColor {
+        self.shadow_color.unwrap_or_else(|| {
+            let (r, g, b) = color.shadow_rgb();
+            TextColor::Rgb { r, g, b }
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -68,25 +228,100 @@ pub struct PositionedGlyph {
     pub ch: char,
     pub x: f32,
     pub y: f32,
+    pub advance: f32,
+    pub span_index: usize,
     pub size: f32,
     pub color: TextColor,
     pub variant: FontVariant,
+    pub family: FontFamily,
+    pub custom_font: Option<Arc<str>>,
+    pub pixel_scale: Option<u32>,
+    pub is_shadow: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecorationKind {
+    Underline,
+    Strikethrough,
+}
+
+#[derive(Debug, Clone)]
+pub struct PositionedDecoration {
+    pub kind: DecorationKind,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub thickness: f32,
+    pub color: TextColor,
     pub is_shadow: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct PositionedBackground {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub color: TextColor,
+    pub alpha: u8,
+}
+
 #[derive(Debug, Clone)]
 pub struct TextLayout {
     pub glyphs: Vec<PositionedGlyph>,
+    pub decorations: Vec<PositionedDecoration>,
+    pub backgrounds: Vec<PositionedBackground>,
     pub width: f32,
     pub height: f32,
+    pub line_count: usize,
+    pub baseline: f32,
+    pub outline: Option<(TextColor, f32)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TextBounds {
+    pub width: f32,
+    pub height: f32,
+    pub line_count: usize,
+    pub baseline: f32,
 }
 
 impl TextLayout {
     pub fn new() -> Self {
         Self {
             glyphs: Vec::new(),
+            decorations: Vec::new(),
+            backgrounds: Vec::new(),
             width: 0.0,
             height: 0.0,
+            line_count: 0,
+            baseline: 0.0,
+            outline: None,
+        }
+    }
+
+    pub fn translate(&mut self, dx: f32, dy: f32) {
+        for glyph in &mut self.glyphs {
+            glyph.x += dx;
+            glyph.y += dy;
+        }
+        for decoration in &mut self.decorations {
+            decoration.x += dx;
+            decoration.y += dy;
+        }
+        for background in &mut self.backgrounds {
+            background.x += dx;
+            background.y += dy;
+        }
+        self.baseline += dy;
+    }
+
+    pub fn bounds(&self) -> TextBounds {
+        TextBounds {
+            width: self.width,
+            height: self.height,
+            line_count: self.line_count,
+            baseline: self.baseline,
         }
     }
 }
@@ -101,14 +336,13 @@ impl Default for TextLayout {
 struct Glyph {
     ch: char,
     advance: f32,
+    span_index: usize,
     color: TextColor,
     variant: FontVariant,
-}
-
-enum Token {
-    Word(Vec<Glyph>),
-    Space(Glyph),
-    Newline,
+    family: FontFamily,
+    obfuscated: bool,
+    underlined: bool,
+    strikethrough: bool,
 }
 
 pub struct LayoutEngine<'a> {
@@ -120,110 +354,115 @@ impl<'a> LayoutEngine<'a> {
         Self { font_system }
     }
 
+    pub fn font_system(&self) -> &'a FontSystem {
+        self.font_system
+    }
+
     pub fn layout(&self, text: &MCText, options: &LayoutOptions) -> TextLayout {
         self.layout_at(text, 0.0, 0.0, options)
     }
 
-    fn tokenize(&self, text: &MCText, size: f32) -> Vec<Token> {
+    fn tokenize(&self, text: &MCText, options: &LayoutOptions) -> Vec<Token<Glyph>> {
+        let size = options.size;
         let default_color = TextColor::default();
-        let mut tokens = Vec::new();
-        let mut current_word = Vec::new();
-
-        for span in text.spans() {
-            let color = span.color.unwrap_or(default_color);
-            let variant = FontVariant::from_style(span.style.bold, span.style.italic);
-
-            for ch in span.text.chars() {
-                match ch {
-                    '\n' => {
-                        if !current_word.is_empty() {
-                            tokens.push(Token::Word(std::mem::take(&mut current_word)));
-                        }
-                        tokens.push(Token::Newline);
-                    }
-                    ' ' => {
-                        if !current_word.is_empty() {
-                            tokens.push(Token::Word(std::mem::take(&mut current_word)));
-                        }
-                        tokens.push(Token::Space(Glyph {
-                            ch: ' ',
-                            advance: self.font_system.measure_char(' ', size, variant),
-                            color,
-                            variant,
-                        }));
-                    }
-                    _ if !ch.is_control() => {
-                        current_word.push(Glyph {
-                            ch,
-                            advance: self.font_system.measure_char(ch, size, variant),
-                            color,
-                            variant,
-                        });
-                    }
-                    _ => {}
+        let mut scrambler = options.obfuscation_seed.map(Scrambler::new);
+        let custom_font = self.custom_font(options);
+
+        wrap::tokenize(text, |ch, span_index, span| {
+            let variant = options
+                .force_variant
+                .unwrap_or_else(|| FontVariant::from_style(span.style.bold, span.style.italic));
+            let family = options
+                .force_family
+                .unwrap_or_else(|| span.font_family().unwrap_or_default());
+            let measure = |ch| match custom_font {
+                Some(name) => self.font_system.measure_char_named(ch, size, name),
+                None if family != FontFamily::Minecraft => self
+                    .font_system
+                    .measure_char_family_styled(ch, size, family, variant),
+                None => self.font_system.measure_char(ch, size, variant),
+            };
+            let advance = measure(ch);
+            let ch = match scrambler.as_mut() {
+                Some(scrambler) if span.style.obfuscated && !matches!(ch, ' ' | '\t') => {
+                    scrambler.substitute(ch, advance, (family, variant), measure)
                 }
+                _ => ch,
+            };
+            Glyph {
+                ch,
+                advance: advance + options.letter_spacing,
+                span_index,
+                color: span.color.unwrap_or(default_color),
+                variant,
+                family,
+                obfuscated: span.style.obfuscated,
+                underlined: span.style.underlined,
+                strikethrough: span.style.strikethrough,
             }
-        }
-
-        if !current_word.is_empty() {
-            tokens.push(Token::Word(current_word));
-        }
-
-        tokens
+        })
     }
 
-    pub fn layout_at(&self, text: &MCText, x: f32, y: f32, options: &LayoutOptions) -> TextLayout {
-        let tokens = self.tokenize(text, options.size);
-        let mut lines: Vec<Vec<Glyph>> = vec![Vec::new()];
-        let mut cursor_x = 0.0f32;
-        let mut max_width = 0.0f32;
+    fn apply_kerning(&self, tokens: &mut [Token<Glyph>], options: &LayoutOptions) {
+        if !options.kerning {
+            return;
+        }
+        let custom_font = self.custom_font(options);
 
         for token in tokens {
-            match token {
-                Token::Newline => {
-                    max_width = max_width.max(cursor_x);
-                    cursor_x = 0.0;
-                    lines.push(Vec::new());
-                }
-                Token::Space(glyph) => {
-                    if let Some(max_w) = options.max_width {
-                        if cursor_x + glyph.advance > max_w && cursor_x > 0.0 {
-                            max_width = max_width.max(cursor_x);
-                            cursor_x = 0.0;
-                            lines.push(Vec::new());
-                            continue;
-                        }
-                    }
-                    lines.last_mut().unwrap().push(glyph.clone());
-                    cursor_x += glyph.advance;
+            let Token::Word(glyphs) = token else {
+                continue;
+            };
+            for i in 1..glyphs.len() {
+                let (left, right) = (&glyphs[i - 1], &glyphs[i]);
+                if left.obfuscated
+                    || right.obfuscated
+                    || left.variant != right.variant
+                    || left.family != right.family
+                {
+                    continue;
                 }
-                Token::Word(glyphs) => {
-                    let word_width: f32 = glyphs.iter().map(|g| g.advance).sum();
-
-                    if let Some(max_w) = options.max_width {
-                        if cursor_x > 0.0 && cursor_x + word_width > max_w {
-                            max_width = max_width.max(cursor_x);
-                            cursor_x = 0.0;
-                            lines.push(Vec::new());
-                        }
-                    }
-
-                    for glyph in glyphs {
-                        if let Some(max_w) = options.max_width {
-                            if cursor_x + glyph.advance > max_w && cursor_x > 0.0 {
-                                max_width = max_width.max(cursor_x);
-                                cursor_x = 0.0;
-                                lines.push(Vec::new());
-                            }
-                        }
-                        lines.last_mut().unwrap().push(glyph.clone());
-                        cursor_x += glyph.advance;
+                let kern = match custom_font {
+                    Some(name) => {
+                        self.font_system
+                            .kerning_named(left.ch, right.ch, options.size, name)
                     }
-                }
+                    None if left.family != FontFamily::Minecraft => 0.0,
+                    None => self
+                        .font_system
+                        .kerning(left.ch, right.ch, options.size, left.variant),
+                };
+                glyphs[i - 1].advance += kern;
             }
         }
+    }
+
+    fn custom_font<'o>(&self, options: &'o LayoutOptions) -> Option<&'o str> {
+        options
+            .custom_font
+            .as_deref()
+            .filter(|name| self.font_system.has_custom_font(name))
+    }
 
-        max_width = max_width.max(cursor_x);
+    pub fn layout_at(&self, text: &MCText, x: f32, y: f32, options: &LayoutOptions) -> TextLayout {
+        let mut tokens = self.tokenize(text, options);
+        self.apply_kerning(&mut tokens, options);
+        let tab_width = options
+            .tab_width
+            .unwrap_or_else(|| self.font_system.tab_width(options.size));
+        let wrap_width = options
+            .max_width
+            .map(|width| width + options.letter_spacing);
+        let (mut lines, mut max_width) =
+            wrap::break_lines(tokens, wrap_width, |glyph: &mut Glyph, cursor_x| {
+                if glyph.ch == '\t' {
+                    glyph.advance = wrap::tab_advance(cursor_x, tab_width);
+                }
+                glyph.advance
+            });
+        if options.letter_spacing != 0.0 {
+            max_width = trim_letter_spacing(&mut lines, options.letter_spacing);
+        }
         self.build_layout(lines, max_width, x, y, options)
     }
 
@@ -235,34 +474,100 @@ impl<'a> LayoutEngine<'a> {
         y: f32,
         options: &LayoutOptions,
     ) -> TextLayout {
-        let ascent = self.font_system.ascent_ratio(FontVariant::Regular) * options.size;
-        let shadow_offset = options.size * SHADOW_OFFSET_RATIO;
+        let custom_font = self.custom_font(options);
+        let line_ascent = |line: &[Glyph]| {
+            (match custom_font {
+                Some(name) => self.font_system.ascent_ratio_named(name),
+                None => line
+                    .iter()
+                    .map(|glyph| {
+                        self.font_system
+                            .ascent_ratio_family(glyph.family, glyph.variant)
+                    })
+                    .reduce(f32::max)
+                    .unwrap_or_else(|| self.font_system.ascent_ratio(FontVariant::Regular)),
+            }) * options.size
+        };
+        let first_ascent = line_ascent(lines.first().map(Vec::as_slice).unwrap_or_default());
+        let custom_font: Option<Arc<str>> = custom_font.map(Arc::from);
+        let (shadow_dx, shadow_dy) = options.shadow_offset();
+        let pixel = options.size * SHADOW_OFFSET_RATIO;
 
         let line_count = lines.len() as f32;
         let gap_count = (lines.len().saturating_sub(1)) as f32;
-        let total_height = line_count * options.size + gap_count * options.line_spacing;
+        let total_height =
+            line_count * options.size + gap_count * (options.line_advance() - options.size);
+
+        let y = match options.vertical_anchor {
+            VerticalAnchor::Top => y,
+            VerticalAnchor::Baseline => y - first_ascent,
+            VerticalAnchor::Middle => y - total_height / 2.0,
+            VerticalAnchor::Bottom => y - total_height,
+        };
+
+        let max_width = match options.align {
+            TextAlign::Left => max_width,
+            TextAlign::Center | TextAlign::Right => {
+                options.max_width.unwrap_or(max_width).max(max_width)
+            }
+        };
 
         let mut glyphs = Vec::new();
-        let mut current_y = y + ascent;
+        let mut decorations = Vec::new();
+        let mut backgrounds = Vec::new();
+        let mut background_bottom = f32::NEG_INFINITY;
+        let mut line_top = y;
 
         for line in &lines {
+            let current_y = line_top + line_ascent(line);
             let line_width: f32 = line.iter().map(|g| g.advance).sum();
+            let trailing: f32 = line
+                .iter()
+                .rev()
+                .take_while(|g| matches!(g.ch, ' ' | '\t'))
+                .map(|g| g.advance)
+                .sum();
             let x_offset = match options.align {
                 TextAlign::Left => x,
-                TextAlign::Center => x + (max_width - line_width) / 2.0,
-                TextAlign::Right => x + max_width - line_width,
+                TextAlign::Center => x + (max_width - line_width + trailing) / 2.0,
+                TextAlign::Right => x + max_width - line_width + trailing,
             };
 
+            if let Some((color, padding)) = options.background {
+                let top = (line_top - padding).max(background_bottom);
+                let bottom = line_top + options.size + padding;
+                if line_width > 0.0 && bottom > top {
+                    backgrounds.push(PositionedBackground {
+                        x: x_offset - padding,
+                        y: top,
+                        width: line_width + padding * 2.0,
+                        height: bottom - top,
+                        color,
+                        alpha: options.background_alpha,
+                    });
+                    background_bottom = bottom;
+                }
+            }
+
             let mut gx = x_offset;
             for glyph in line {
+                if glyph.ch == '\t' {
+                    gx += glyph.advance;
+                    continue;
+                }
                 if options.shadow {
                     glyphs.push(PositionedGlyph {
                         ch: glyph.ch,
-                        x: gx + shadow_offset,
-                        y: current_y + shadow_offset,
+                        x: gx + shadow_dx,
+                        y: current_y + shadow_dy,
+                        advance: glyph.advance,
+                        span_index: glyph.span_index,
                         size: options.size,
-                        color: glyph.color,
+                        color: options.shadow_color_for(glyph.color),
                         variant: glyph.variant,
+                        family: glyph.family,
+                        custom_font: custom_font.clone(),
+                        pixel_scale: options.pixel_scale,
                         is_shadow: true,
                     });
                 }
@@ -270,21 +575,59 @@ impl<'a> LayoutEngine<'a> {
                     ch: glyph.ch,
                     x: gx,
                     y: current_y,
+                    advance: glyph.advance,
+                    span_index: glyph.span_index,
                     size: options.size,
                     color: glyph.color,
                     variant: glyph.variant,
+                    family: glyph.family,
+                    custom_font: custom_font.clone(),
+                    pixel_scale: options.pixel_scale,
                     is_shadow: false,
                 });
                 gx += glyph.advance;
             }
 
-            current_y += options.size + options.line_spacing;
+            for kind in [DecorationKind::Underline, DecorationKind::Strikethrough] {
+                for (start, width, color) in decoration_runs(line, kind) {
+                    let offset = match kind {
+                        DecorationKind::Underline => 1.0,
+                        DecorationKind::Strikethrough => -3.5,
+                    };
+                    let decoration = PositionedDecoration {
+                        kind,
+                        x: x_offset + start,
+                        y: current_y + offset * pixel,
+                        width,
+                        thickness: pixel,
+                        color,
+                        is_shadow: false,
+                    };
+                    if options.shadow {
+                        decorations.push(PositionedDecoration {
+                            x: decoration.x + shadow_dx,
+                            y: decoration.y + shadow_dy,
+                            color: options.shadow_color_for(decoration.color),
+                            is_shadow: true,
+                            ..decoration.clone()
+                        });
+                    }
+                    decorations.push(decoration);
+                }
+            }
+
+            line_top += options.line_advance();
         }
 
         TextLayout {
             glyphs,
+            decorations,
+            backgrounds,
             width: max_width,
             height: total_height,
+            line_count: lines.len(),
+            baseline: y + first_ascent,
+            outline: options.outline,
         }
     }
 
@@ -295,9 +638,89 @@ impl<'a> LayoutEngine<'a> {
     }
 }
 
+struct Scrambler {
+    state: u64,
+    pools: HashMap<(FontFamily, FontVariant), Vec<(char, f32)>>,
+}
+
+impl Scrambler {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            pools: HashMap::new(),
+        }
+    }
+
+    fn substitute(
+        &mut self,
+        ch: char,
+        advance: f32,
+        key: (FontFamily, FontVariant),
+        measure: impl Fn(char) -> f32,
+    ) -> char {
+        let pool = self
+            .pools
+            .entry(key)
+            .or_insert_with(|| OBFUSCATION_POOL.chars().map(|c| (c, measure(c))).collect());
+
+        let candidates: Vec<char> = pool
+            .iter()
+            .filter(|(_, width)| (width - advance).abs() < 0.01)
+            .map(|(c, _)| *c)
+            .collect();
+
+        let roll = next_random(&mut self.state);
+        if candidates.is_empty() {
+            ch
+        } else {
+            candidates[(roll % candidates.len() as u64) as usize]
+        }
+    }
+}
+
+fn trim_letter_spacing(lines: &mut [Vec<Glyph>], spacing: f32) -> f32 {
+    let mut widest = 0.0f32;
+    for line in lines {
+        if let Some(last) = line.last_mut().filter(|glyph| glyph.ch != '\t') {
+            last.advance -= spacing;
+        }
+        widest = widest.max(line.iter().map(|glyph| glyph.advance).sum());
+    }
+    widest
+}
+
+fn decoration_runs(line: &[Glyph], kind: DecorationKind) -> Vec<(f32, f32, TextColor)> {
+    let mut runs: Vec<(f32, f32, TextColor)> = Vec::new();
+    let mut x = 0.0f32;
+    let mut extending = false;
+
+    for glyph in line {
+        let decorated = match kind {
+            DecorationKind::Underline => glyph.underlined,
+            DecorationKind::Strikethrough => glyph.strikethrough,
+        };
+
+        if decorated {
+            match runs.last_mut() {
+                Some(run) if extending && run.2 == glyph.color => run.1 += glyph.advance,
+                _ => runs.push((x, glyph.advance, glyph.color)),
+            }
+        }
+        extending = decorated;
+        x += glyph.advance;
+    }
+
+    runs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::color::NamedColor;
+    #[cfg(feature = "special-fonts")]
+    use crate::style::Style;
+    #[cfg(feature = "special-fonts")]
+    use crate::text::Span;
 
     fn test_system() -> FontSystem {
         FontSystem::modern()
@@ -314,6 +737,426 @@ mod tests {
         assert!(layout.width > 0.0);
     }
 
+    #[test]
+    fn test_layout_span_index() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::parse("a\u{00A7}cbc");
+        let layout = engine.layout(&text, &LayoutOptions::new(16.0).with_shadow(false));
+
+        let indices: Vec<_> = layout.glyphs.iter().map(|g| g.span_index).collect();
+        assert_eq!(indices, vec![0, 1, 1]);
+        assert_eq!(layout.glyphs[1].x, layout.glyphs[0].advance);
+    }
+
+    #[test]
+    fn test_layout_background() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::new().span("ab\nc").build();
+        let options = LayoutOptions::new(16.0)
+            .with_shadow(false)
+            .with_background(NamedColor::Black, 2.0)
+            .with_background_alpha(128);
+        let layout = engine.layout(&text, &options);
+
+        let [first, second] = layout.backgrounds.as_slice() else {
+            panic!("expected one background per line");
+        };
+        assert_eq!(first.x, -2.0);
+        assert_eq!(first.width, system.measure_text("ab", 16.0) + 4.0);
+        assert_eq!(first.y + first.height, second.y);
+        assert_eq!(second.alpha, 128);
+    }
+
+    #[test]
+    #[cfg(feature = "special-fonts")]
+    fn test_layout_font_family() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let mut text = MCText::new();
+        text.push(Span::new("rune").with_font("minecraft:alt"));
+        let layout = engine.layout(&text, &LayoutOptions::new(16.0).with_shadow(false));
+
+        assert!(
+            layout
+                .glyphs
+                .iter()
+                .all(|g| g.family == FontFamily::Enchanting)
+        );
+        assert_eq!(
+            layout.width,
+            system.measure_text_family("rune", 16.0, FontFamily::Enchanting)
+        );
+
+        let mut bold = MCText::new();
+        bold.push(
+            Span::new("rune")
+                .with_font("minecraft:alt")
+                .with_style(Style {
+                    bold: true,
+                    ..Style::default()
+                }),
+        );
+        let bold_layout = engine.layout(&bold, &LayoutOptions::new(16.0).with_shadow(false));
+        assert_eq!(
+            bold_layout.width,
+            system.measure_text_family_styled(
+                "rune",
+                16.0,
+                FontFamily::Enchanting,
+                FontVariant::Bold
+            )
+        );
+        let extra = system.synthetic_bold_offset(16.0) * 4.0;
+        assert!((bold_layout.width - layout.width - extra).abs() < 0.001);
+
+        let mut mixed = MCText::parse("Enchant ");
+        mixed.push(Span::new("rune").with_font("minecraft:alt"));
+        let mixed_layout = engine.layout(&mixed, &LayoutOptions::new(16.0).with_shadow(false));
+        let ascent = [FontFamily::Minecraft, FontFamily::Enchanting]
+            .map(|family| system.ascent_ratio_family(family, FontVariant::Regular) * 16.0);
+        assert!(
+            mixed_layout
+                .glyphs
+                .iter()
+                .any(|g| g.family == FontFamily::Minecraft)
+        );
+        assert!(
+            mixed_layout
+                .glyphs
+                .iter()
+                .all(|g| g.y == mixed_layout.baseline)
+        );
+        assert_eq!(mixed_layout.baseline, ascent[0].max(ascent[1]));
+
+        let forced = engine.layout(
+            &mixed,
+            &LayoutOptions::new(16.0)
+                .with_shadow(false)
+                .with_force_family(FontFamily::Enchanting),
+        );
+        assert!(
+            forced
+                .glyphs
+                .iter()
+                .all(|g| g.family == FontFamily::Enchanting)
+        );
+    }
+
+    #[test]
+    fn test_layout_decorations() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::parse("§nab §mcd§r ef");
+        let layout = engine.layout(&text, &LayoutOptions::new(16.0).with_shadow(false));
+
+        assert_eq!(layout.decorations.len(), 2);
+        let underline_width: f32 = layout
+            .decorations
+            .iter()
+            .filter(|d| d.kind == DecorationKind::Underline)
+            .map(|d| d.width)
+            .sum();
+        let expected = system.measure_text("ab cd", 16.0);
+        assert!((underline_width - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_layout_align() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::new().span("ab").build();
+        let width = system.measure_text("ab", 16.0);
+        let first_x = |align| {
+            let options = LayoutOptions::new(16.0)
+                .with_shadow(false)
+                .with_max_width(100.0)
+                .with_align(align);
+            engine.layout(&text, &options).glyphs[0].x
+        };
+
+        assert_eq!(first_x(TextAlign::Left), 0.0);
+        assert!((first_x(TextAlign::Center) - (100.0 - width) / 2.0).abs() < 0.001);
+        assert!((first_x(TextAlign::Right) - (100.0 - width)).abs() < 0.001);
+
+        let text = MCText::new().span("aaaa bbbb").build();
+        let options = LayoutOptions::new(16.0)
+            .with_shadow(false)
+            .with_max_width(system.measure_text("aaaa ", 16.0) + 1.0)
+            .with_align(TextAlign::Right);
+        let layout = engine.layout(&text, &options);
+        let right_edge = |ch| {
+            layout
+                .glyphs
+                .iter()
+                .filter(|g| g.ch == ch)
+                .map(|g| g.x + g.advance)
+                .fold(f32::NEG_INFINITY, f32::max)
+        };
+        assert_eq!(layout.line_count, 2);
+        assert!((right_edge('a') - right_edge('b')).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_layout_mixed_variant_baseline() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::new().span("Ab").then("Cd").bold().build();
+        let layout = engine.layout(&text, &LayoutOptions::new(16.0).with_shadow(false));
+        let ascent = system
+            .ascent_ratio(FontVariant::Regular)
+            .max(system.ascent_ratio(FontVariant::Bold))
+            * 16.0;
+
+        assert!(layout.glyphs.iter().all(|g| g.y == layout.baseline));
+        assert_eq!(layout.baseline, ascent);
+    }
+
+    #[test]
+    fn test_layout_kerning() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::parse("AVAV To");
+        let options = LayoutOptions::new(16.0).with_shadow(false);
+        let kerned = engine.layout(&text, &options);
+        let unkerned = engine.layout(&text, &options.clone().with_kerning(false));
+
+        assert_eq!(kerned.width, system.measure_text("AVAV To", 16.0));
+        assert_eq!(
+            unkerned.width,
+            "AVAV To"
+                .chars()
+                .map(|ch| system.measure_char(ch, 16.0, FontVariant::Regular))
+                .sum::<f32>()
+        );
+    }
+
+    #[test]
+    fn test_layout_shadow_options() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::new().span("A").color(NamedColor::Red).build();
+
+        let layout = engine.layout(&text, &LayoutOptions::new(12.0));
+        let (shadow, main) = (&layout.glyphs[0], &layout.glyphs[1]);
+        assert!(shadow.is_shadow && !main.is_shadow);
+        assert_eq!((shadow.x - main.x, shadow.y - main.y), (1.0, 1.0));
+        let (r, g, b) = NamedColor::Red.rgb();
+        assert_eq!(shadow.color.rgb(), (r / 4, g / 4, b / 4));
+
+        let options = LayoutOptions::new(12.0)
+            .with_shadow_color(NamedColor::Blue)
+            .with_shadow_offset(2.0, 3.0);
+        let layout = engine.layout(&text, &options);
+        let (shadow, main) = (&layout.glyphs[0], &layout.glyphs[1]);
+        assert_eq!((shadow.x - main.x, shadow.y - main.y), (2.0, 3.0));
+        assert_eq!(shadow.color, TextColor::Named(NamedColor::Blue));
+    }
+
+    #[test]
+    fn test_line_height() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::new().span("a\nb").build();
+        let layout = |options: LayoutOptions| engine.layout(&text, &options.with_shadow(false));
+
+        let default = layout(LayoutOptions::new(16.0));
+        assert_eq!(default.height, 31.0);
+        assert_eq!(
+            layout(LayoutOptions::new(16.0).with_line_height(-1.0)).height,
+            31.0
+        );
+
+        let spaced = layout(LayoutOptions::new(16.0).with_line_height(2.0));
+        assert_eq!(spaced.height, 48.0);
+        assert_eq!(spaced.glyphs[1].y - spaced.glyphs[0].y, 32.0);
+    }
+
+    #[test]
+    fn test_vertical_anchor() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::parse("a\nb");
+        let layout = |anchor| {
+            let options = LayoutOptions::new(16.0)
+                .with_shadow(false)
+                .with_vertical_anchor(anchor);
+            engine.layout_at(&text, 0.0, 100.0, &options)
+        };
+
+        let top = layout(VerticalAnchor::Top);
+        let ascent = top.baseline - 100.0;
+        assert_eq!(layout(VerticalAnchor::Baseline).baseline, 100.0);
+        assert_eq!(
+            layout(VerticalAnchor::Middle).baseline,
+            100.0 - top.height / 2.0 + ascent
+        );
+        let bottom = layout(VerticalAnchor::Bottom);
+        assert_eq!(bottom.baseline, 100.0 - top.height + ascent);
+        assert_eq!(bottom.glyphs[0].y, bottom.baseline);
+    }
+
+    #[test]
+    fn test_invalid_sizes() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::parse("Hello world");
+
+        for size in [0.0, -4.0, f32::NAN, f32::INFINITY] {
+            let options = LayoutOptions::new(size);
+            assert_eq!(options.size, MIN_SIZE);
+            assert_eq!(
+                LayoutOptions::try_new(size).err(),
+                Some(RenderError::InvalidSize)
+            );
+            assert!(engine.layout(&text, &options).width.is_finite());
+        }
+
+        let options = LayoutOptions::new(16.0).with_max_width(0.0);
+        assert_eq!(options.max_width, None);
+        assert_eq!(
+            options.try_with_max_width(-1.0).err(),
+            Some(RenderError::InvalidMaxWidth)
+        );
+    }
+
+    #[test]
+    fn test_layout_tabs() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::new().span("a\tb").build();
+        let options = LayoutOptions::new(16.0).with_shadow(false);
+
+        let layout = engine.layout(&text, &options);
+        assert_eq!(layout.glyphs.len(), 2);
+        assert_eq!(layout.glyphs[1].x, system.tab_width(16.0));
+        assert_eq!(layout.width, system.measure_text("a\tb", 16.0));
+
+        let layout = engine.layout(&text, &options.with_tab_width(50.0));
+        assert_eq!(layout.glyphs[1].x, 50.0);
+    }
+
+    #[test]
+    fn test_obfuscation_seed() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::parse("§kHello World");
+        let plain = engine.layout(&text, &LayoutOptions::new(16.0).with_shadow(false));
+        let frame = |seed| {
+            let options = LayoutOptions::new(16.0)
+                .with_shadow(false)
+                .with_obfuscation_seed(seed);
+            engine.layout(&text, &options)
+        };
+        let (a, b) = (frame(1), frame(2));
+
+        let chars = |layout: &TextLayout| layout.glyphs.iter().map(|g| g.ch).collect::<String>();
+        assert_eq!(chars(&plain), "Hello World");
+        assert_eq!(chars(&a), chars(&frame(1)));
+        assert_ne!(chars(&a), chars(&b));
+        assert!((a.width - plain.width).abs() < 0.01);
+        for (glyph, original) in a.glyphs.iter().zip(&plain.glyphs) {
+            assert!((glyph.x - original.x).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "special-fonts")]
+    fn test_obfuscated_font_family() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let mut text = MCText::new();
+        text.push(
+            Span::new("abcdefgh")
+                .with_font("minecraft:alt")
+                .with_style(Style {
+                    obfuscated: true,
+                    ..Style::default()
+                }),
+        );
+        let mut scrambled = false;
+        for seed in 0..8 {
+            let options = LayoutOptions::new(16.0)
+                .with_shadow(false)
+                .with_obfuscation_seed(seed);
+            let layout = engine.layout(&text, &options);
+            for (glyph, original) in layout.glyphs.iter().zip("abcdefgh".chars()) {
+                let width = system.measure_char_family(glyph.ch, 16.0, FontFamily::Enchanting);
+                assert!((width - glyph.advance).abs() < 0.01);
+                scrambled |= glyph.ch != original;
+            }
+        }
+        assert!(scrambled);
+    }
+
+    #[test]
+    fn test_obfuscated_width_is_stable() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::parse("§kabcd");
+        let expected = system.measure_text("§kabcd", 16.0);
+        for seed in 0..16 {
+            let options = LayoutOptions::new(16.0)
+                .with_shadow(false)
+                .with_obfuscation_seed(seed);
+            assert!((engine.layout(&text, &options).width - expected).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_force_variant() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::parse("a§lb§r§oc");
+        let options = LayoutOptions::new(16.0).with_shadow(false);
+
+        let derived = engine.layout(&text, &options);
+        let variants =
+            |layout: &TextLayout| layout.glyphs.iter().map(|g| g.variant).collect::<Vec<_>>();
+        assert_eq!(
+            variants(&derived),
+            [FontVariant::Regular, FontVariant::Bold, FontVariant::Italic]
+        );
+
+        let forced = engine.layout(
+            &text,
+            &options.clone().with_force_variant(FontVariant::Italic),
+        );
+        assert_eq!(variants(&forced), [FontVariant::Italic; 3]);
+        assert!(
+            forced
+                .glyphs
+                .iter()
+                .all(|g| g.family == FontFamily::Minecraft)
+        );
+    }
+
+    #[test]
+    fn test_letter_spacing() {
+        let system = test_system();
+        let engine = LayoutEngine::new(&system);
+        let text = MCText::parse("abc");
+        let base = LayoutOptions::new(16.0)
+            .with_shadow(false)
+            .with_kerning(false);
+        let plain = engine.layout(&text, &base);
+        let spaced = engine.layout(&text, &base.clone().with_letter_spacing(2.0));
+        let tight = engine.layout(&text, &base.clone().with_letter_spacing(-1.0));
+
+        assert!((spaced.width - plain.width - 4.0).abs() < 0.001);
+        assert!((tight.width - plain.width + 2.0).abs() < 0.001);
+        assert!((spaced.glyphs[2].x - plain.glyphs[2].x - 4.0).abs() < 0.001);
+
+        let measured = system.measure_text_spaced("§cabc", 16.0, 2.0);
+        assert!((measured - system.measure_text("abc", 16.0) - 4.0).abs() < 0.001);
+        let kerned = engine.layout(&text, &LayoutOptions::new(16.0).with_letter_spacing(2.0));
+        assert!((kerned.width - measured).abs() < 0.001);
+
+        let fitted = base.with_letter_spacing(2.0).with_max_width(spaced.width);
+        assert_eq!(engine.layout(&text, &fitted).line_count, 1);
+    }
+
     #[test]
     fn test_measure() {
         let system = test_system();
@@ -1,8 +1,17 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 mod color;
+mod event;
 pub mod fonts;
 mod json;
+mod math;
+mod minimessage;
+mod snbt;
 mod style;
 mod text;
+mod wrap;
 
 #[cfg(feature = "render")]
 mod layout;
@@ -11,7 +20,10 @@ mod render;
 #[cfg(feature = "render")]
 mod system;
 
+#[cfg(feature = "bedrock-colors")]
+pub use color::BedrockColor;
 pub use color::{NamedColor, SHADOW_OFFSET, TextColor, shadow_color};
+pub use event::{ClickEvent, HoverEvent};
 pub use fonts::{FontFamily, FontVariant, FontVersion};
 
 #[cfg(feature = "modern-fonts")]
@@ -23,13 +35,31 @@ pub use fonts::{LEGACY_BOLD, LEGACY_BOLD_ITALIC, LEGACY_ITALIC, LEGACY_REGULAR};
 #[cfg(feature = "special-fonts")]
 pub use fonts::{ENCHANTING_REGULAR, ILLAGER_REGULAR};
 
-pub use json::{ParseError, to_json, try_parse_json_component};
-pub use style::Style;
-pub use text::{MCText, Span, SpanBuilder, count_visible_chars, strip_codes};
+pub use json::{
+    ParseError, ParseMode, to_json, to_json_compact, try_parse_json_component,
+    try_parse_json_component_with,
+};
+pub use snbt::parse_snbt_component;
+pub use style::{OptStyle, Style};
+pub use text::{
+    ComponentContent, MCText, ParseOptions, Segment, Span, SpanBuilder, Translation, UnknownCode,
+    count_visible_chars, parse_iter, repair_section_signs, strip_codes,
+};
 
+#[cfg(feature = "unicode")]
+pub use text::count_visible_graphemes;
+
+#[cfg(feature = "render")]
+pub use layout::{
+    DecorationKind, LayoutEngine, LayoutOptions, PositionedBackground, PositionedDecoration,
+    PositionedGlyph, TextAlign, TextBounds, TextLayout, VerticalAnchor,
+};
 #[cfg(feature = "render")]
-pub use layout::{LayoutEngine, LayoutOptions, PositionedGlyph, TextAlign, TextLayout};
+pub use render::{
+    RasterizedGlyph, RenderError, SoftwareRenderer, TextRenderContext, TextRenderer,
+    blend_image_over, blend_over, blend_over_linear,
+};
 #[cfg(feature = "render")]
-pub use render::{RasterizedGlyph, SoftwareRenderer, TextRenderContext, TextRenderer};
+pub use system::{FontError, FontSystem, GlyphMetrics, LineMetrics, VerticalMetrics};
 #[cfg(feature = "render")]
-pub use system::{FontSystem, GlyphMetrics};
+pub use wrap::SIGN_LINE_WIDTH;
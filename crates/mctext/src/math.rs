@@ -0,0 +1,48 @@
+#[cfg(feature = "std")]
+mod imp {
+    pub fn floor(x: f32) -> f32 {
+        x.floor()
+    }
+
+    pub fn round(x: f32) -> f32 {
+        x.round()
+    }
+
+    pub fn powf(x: f32, y: f32) -> f32 {
+        x.powf(y)
+    }
+
+    pub fn cbrt(x: f32) -> f32 {
+        x.cbrt()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod imp {
+    pub fn floor(x: f32) -> f32 {
+        libm::floorf(x)
+    }
+
+    pub fn round(x: f32) -> f32 {
+        libm::roundf(x)
+    }
+
+    pub fn powf(x: f32, y: f32) -> f32 {
+        libm::powf(x, y)
+    }
+
+    pub fn cbrt(x: f32) -> f32 {
+        libm::cbrtf(x)
+    }
+}
+
+pub(crate) use imp::{cbrt, floor, powf, round};
+
+pub(crate) fn abs(x: f32) -> f32 {
+    if x < 0.0 { -x } else { x }
+}
+
+pub(crate) fn rem_euclid(x: f32, modulus: f32) -> f32 {
+    let r = x % modulus;
+    if r < 0.0 { r + modulus } else { r }
+}
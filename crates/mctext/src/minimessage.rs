@@ -0,0 +1,474 @@
+use crate::color::{NamedColor, TextColor};
+use crate::json::ParseError;
+use crate::math;
+use crate::style::Style;
+use crate::text::{MCText, Span};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decoration {
+    Bold,
+    Italic,
+    Underlined,
+    Strikethrough,
+    Obfuscated,
+}
+
+impl Decoration {
+    fn from_name(name: &str) -> Option<Decoration> {
+        match name {
+            "bold" | "b" => Some(Decoration::Bold),
+            "italic" | "i" | "em" => Some(Decoration::Italic),
+            "underlined" | "u" => Some(Decoration::Underlined),
+            "strikethrough" | "st" => Some(Decoration::Strikethrough),
+            "obfuscated" | "obf" => Some(Decoration::Obfuscated),
+            _ => None,
+        }
+    }
+
+    fn apply(self, style: &mut Style, value: bool) {
+        match self {
+            Decoration::Bold => style.bold = value,
+            Decoration::Italic => style.italic = value,
+            Decoration::Underlined => style.underlined = value,
+            Decoration::Strikethrough => style.strikethrough = value,
+            Decoration::Obfuscated => style.obfuscated = value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Fill {
+    Gradient(Vec<TextColor>),
+    Rainbow { phase: f32, reversed: bool },
+}
+
+impl Fill {
+    fn color_at(&self, index: usize, count: usize) -> TextColor {
+        let t = if count > 1 {
+            index as f32 / (count - 1) as f32
+        } else {
+            0.0
+        };
+
+        match self {
+            Fill::Gradient(stops) => {
+                if stops.len() == 1 {
+                    return stops[0];
+                }
+                let scaled = t * (stops.len() - 1) as f32;
+                let segment = (math::floor(scaled) as usize).min(stops.len() - 2);
+                TextColor::lerp(
+                    &stops[segment],
+                    &stops[segment + 1],
+                    scaled - segment as f32,
+                )
+            }
+            Fill::Rainbow { phase, reversed } => {
+                let position = if count > 0 {
+                    index as f32 / count as f32
+                } else {
+                    0.0
+                };
+                let position = if *reversed { 1.0 - position } else { position };
+                TextColor::from_hsv(
+                    math::rem_euclid((position + phase) * 360.0, 360.0),
+                    1.0,
+                    1.0,
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColorSource {
+    Fixed(Option<TextColor>),
+    Fill(usize),
+}
+
+#[derive(Debug, Clone)]
+struct OpenTag {
+    name: String,
+    kind: TagKind,
+}
+
+#[derive(Debug, Clone)]
+enum TagKind {
+    Color(ColorSource),
+    Decoration(Decoration, bool),
+}
+
+struct Piece {
+    text: String,
+    color: ColorSource,
+    style: Style,
+}
+
+struct Parser {
+    stack: Vec<OpenTag>,
+    fills: Vec<(Fill, usize)>,
+    pieces: Vec<Piece>,
+}
+
+impl Parser {
+    fn current_color(&self) -> ColorSource {
+        self.stack
+            .iter()
+            .rev()
+            .find_map(|tag| match tag.kind {
+                TagKind::Color(source) => Some(source),
+                TagKind::Decoration(..) => None,
+            })
+            .unwrap_or(ColorSource::Fixed(None))
+    }
+
+    fn current_style(&self) -> Style {
+        let mut style = Style::default();
+        for tag in &self.stack {
+            if let TagKind::Decoration(decoration, value) = tag.kind {
+                decoration.apply(&mut style, value);
+            }
+        }
+        style
+    }
+
+    fn push_char(&mut self, ch: char) {
+        let color = self.current_color();
+        let style = self.current_style();
+
+        if let ColorSource::Fill(id) = color {
+            self.fills[id].1 += 1;
+            self.pieces.push(Piece {
+                text: ch.to_string(),
+                color,
+                style,
+            });
+            return;
+        }
+
+        if let Some(last) = self.pieces.last_mut() {
+            if last.color == color && last.style == style {
+                last.text.push(ch);
+                return;
+            }
+        }
+
+        self.pieces.push(Piece {
+            text: ch.to_string(),
+            color,
+            style,
+        });
+    }
+
+    fn open_tag(&mut self, content: &str) -> Result<bool, String> {
+        let mut parts = content.split(':');
+        let raw_name = parts.next().unwrap_or_default();
+        let args: Vec<&str> = parts.collect();
+
+        let (negated, name) = match raw_name.strip_prefix('!') {
+            Some(rest) => (true, rest.to_ascii_lowercase()),
+            None => (false, raw_name.to_ascii_lowercase()),
+        };
+
+        if let Some(decoration) = Decoration::from_name(&name) {
+            self.stack.push(OpenTag {
+                name,
+                kind: TagKind::Decoration(decoration, !negated),
+            });
+            return Ok(true);
+        }
+
+        if negated {
+            return Ok(false);
+        }
+
+        let source = match name.as_str() {
+            "reset" => {
+                self.stack.clear();
+                return Ok(true);
+            }
+            "newline" | "br" => {
+                self.push_char('\n');
+                return Ok(true);
+            }
+            "color" | "colour" | "c" => {
+                let [arg] = args.as_slice() else {
+                    return Err(invalid(content));
+                };
+                ColorSource::Fixed(Some(parse_color(arg).ok_or_else(|| invalid(content))?))
+            }
+            "gradient" => {
+                let stops = if args.is_empty() {
+                    vec![
+                        TextColor::Named(NamedColor::White),
+                        TextColor::Named(NamedColor::Black),
+                    ]
+                } else {
+                    args.iter()
+                        .map(|arg| parse_color(arg).ok_or_else(|| invalid(content)))
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+                self.push_fill(Fill::Gradient(stops))
+            }
+            "rainbow" => {
+                let (reversed, phase) = match args.as_slice() {
+                    [] => (false, ""),
+                    [arg] => match arg.strip_prefix('!') {
+                        Some(rest) => (true, rest),
+                        None => (false, *arg),
+                    },
+                    _ => return Err(invalid(content)),
+                };
+                let phase = if phase.is_empty() {
+                    0.0
+                } else {
+                    phase.parse::<f32>().map_err(|_| invalid(content))?
+                };
+                self.push_fill(Fill::Rainbow { phase, reversed })
+            }
+            _ if name.starts_with('#') => {
+                ColorSource::Fixed(Some(parse_color(&name).ok_or_else(|| invalid(content))?))
+            }
+            _ => match NamedColor::from_name(&name) {
+                Some(named) => ColorSource::Fixed(Some(TextColor::Named(named))),
+                None => return Ok(false),
+            },
+        };
+
+        self.stack.push(OpenTag {
+            name,
+            kind: TagKind::Color(source),
+        });
+        Ok(true)
+    }
+
+    fn push_fill(&mut self, fill: Fill) -> ColorSource {
+        self.fills.push((fill, 0));
+        ColorSource::Fill(self.fills.len() - 1)
+    }
+
+    fn close_tag(&mut self, content: &str) -> Result<bool, String> {
+        let name = content.split(':').next().unwrap_or_default();
+        let name = name.strip_prefix('!').unwrap_or(name).to_ascii_lowercase();
+
+        if name == "reset" {
+            return Ok(true);
+        }
+
+        let Some(index) = self.stack.iter().rposition(|tag| {
+            tag.name == name
+                || (name == "color" && matches!(tag.kind, TagKind::Color(ColorSource::Fixed(_))))
+        }) else {
+            if Decoration::from_name(&name).is_some() || is_color_tag(&name) {
+                return Err(format!("unexpected closing tag </{}>", content));
+            }
+            return Ok(false);
+        };
+
+        self.stack.remove(index);
+        Ok(true)
+    }
+
+    fn finish(self) -> MCText {
+        let mut fill_positions = vec![0usize; self.fills.len()];
+        let mut text = MCText::new();
+
+        for piece in self.pieces {
+            let color = match piece.color {
+                ColorSource::Fixed(color) => color,
+                ColorSource::Fill(id) => {
+                    let (fill, count) = &self.fills[id];
+                    let color = fill.color_at(fill_positions[id], *count);
+                    fill_positions[id] += 1;
+                    Some(color)
+                }
+            };
+            text.push(Span {
+                text: piece.text,
+                color,
+                style: piece.style,
+                ..Default::default()
+            });
+        }
+
+        text
+    }
+}
+
+fn is_color_tag(name: &str) -> bool {
+    matches!(name, "color" | "colour" | "c" | "gradient" | "rainbow")
+        || name.starts_with('#')
+        || NamedColor::from_name(name).is_some()
+}
+
+fn parse_color(arg: &str) -> Option<TextColor> {
+    if arg.starts_with('#') {
+        TextColor::from_hex(arg).ok()
+    } else {
+        NamedColor::from_name(arg).map(TextColor::Named)
+    }
+}
+
+fn invalid(content: &str) -> String {
+    format!("invalid tag <{}>", content)
+}
+
+fn is_tag_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '#' | '!' | ':' | '.' | '/')
+}
+
+pub fn parse(input: &str) -> Result<MCText, ParseError> {
+    let mut parser = Parser {
+        stack: Vec::new(),
+        fills: Vec::new(),
+        pieces: Vec::new(),
+    };
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '\\' => match chars.peek() {
+                Some(&(_, next @ ('<' | '\\'))) => {
+                    chars.next();
+                    parser.push_char(next);
+                }
+                _ => parser.push_char(ch),
+            },
+            '<' => {
+                let rest = &input[index + 1..];
+                if !rest.starts_with(is_tag_char) {
+                    parser.push_char(ch);
+                    continue;
+                }
+
+                let Some(end) = rest
+                    .find(['>', '<'])
+                    .filter(|&end| rest[end..].starts_with('>'))
+                else {
+                    return Err(ParseError::InvalidMiniMessage {
+                        message: "unterminated tag".to_string(),
+                        at: index,
+                    });
+                };
+
+                let content = &rest[..end];
+                let handled = match content.strip_prefix('/') {
+                    Some(name) => parser.close_tag(name),
+                    None => parser.open_tag(content),
+                }
+                .map_err(|message| ParseError::InvalidMiniMessage { message, at: index })?;
+
+                if handled {
+                    while chars.peek().is_some_and(|&(i, _)| i <= index + end + 1) {
+                        chars.next();
+                    }
+                } else {
+                    parser.push_char(ch);
+                }
+            }
+            _ => parser.push_char(ch),
+        }
+    }
+
+    Ok(parser.finish())
+}
+
+pub fn serialize(text: &MCText) -> String {
+    let mut result = String::new();
+
+    for span in text.spans() {
+        let mut tags: Vec<String> = Vec::new();
+        match span.color {
+            Some(TextColor::Named(named)) => tags.push(named.name().to_string()),
+            Some(TextColor::Rgb { r, g, b }) => tags.push(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+            None => {}
+        }
+        for (enabled, name) in [
+            (span.style.bold, "bold"),
+            (span.style.italic, "italic"),
+            (span.style.underlined, "underlined"),
+            (span.style.strikethrough, "strikethrough"),
+            (span.style.obfuscated, "obfuscated"),
+        ] {
+            if enabled {
+                tags.push(name.to_string());
+            }
+        }
+
+        for tag in &tags {
+            result.push_str(&format!("<{}>", tag));
+        }
+        for ch in span.text.chars() {
+            if matches!(ch, '<' | '\\') {
+                result.push('\\');
+            }
+            result.push(ch);
+        }
+        for tag in tags.iter().rev() {
+            let name = if tag.starts_with('#') { "color" } else { tag };
+            result.push_str(&format!("</{}>", name));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_minimessage() {
+        let text = parse("<red>Hello <bold>World</bold></red> <#aabbcc>hex <unknown>").unwrap();
+        let spans = text.spans();
+        assert_eq!(text.plain_text(), "Hello World hex <unknown>");
+        assert_eq!(spans[0].color, Some(TextColor::Named(NamedColor::Red)));
+        assert!(spans[1].style.bold);
+        assert_eq!(spans[2].color, None);
+        assert_eq!(
+            spans[3].color,
+            Some(TextColor::Rgb {
+                r: 0xaa,
+                g: 0xbb,
+                b: 0xcc
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_minimessage_gradient() {
+        let text = parse("<gradient:#ff0000:#0000ff>abc</gradient>").unwrap();
+        let colors: Vec<_> = text
+            .spans()
+            .iter()
+            .map(|s| s.color.unwrap().rgb())
+            .collect();
+        assert_eq!(colors, vec![(255, 0, 0), (128, 0, 128), (0, 0, 255)]);
+    }
+
+    #[test]
+    fn test_serialize_minimessage() {
+        let text = MCText::parse(
+            "\u{00A7}cHi \u{00A7}x\u{00A7}1\u{00A7}2\u{00A7}3\u{00A7}4\u{00A7}5\u{00A7}6\u{00A7}la<b",
+        );
+        let serialized = serialize(&text);
+        assert_eq!(
+            serialized,
+            "<red>Hi </red><#123456><bold>a\\<b</bold></color>"
+        );
+        assert_eq!(parse(&serialized).unwrap(), text);
+    }
+
+    #[test]
+    fn test_parse_minimessage_errors() {
+        assert!(parse("<red").is_err());
+        assert!(parse("<color:nope>x").is_err());
+        assert!(parse("x</bold>").is_err());
+        assert_eq!(parse("ab<color:nope>x").unwrap_err().offset(), Some(2));
+        assert!(parse("a < b").is_ok());
+    }
+}
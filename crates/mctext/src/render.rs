@@ -1,7 +1,23 @@
-use crate::fonts::FontVariant;
-use crate::layout::{LayoutEngine, LayoutOptions, TextLayout};
+use crate::color::{linear_to_srgb, srgb_to_linear};
+use crate::fonts::{FontFamily, FontVariant};
+use crate::layout::{LayoutEngine, LayoutOptions, PositionedGlyph, TextBounds, TextLayout};
 use crate::system::FontSystem;
+use crate::system::GlyphMetrics;
 use crate::text::MCText;
+use std::f32::consts::FRAC_1_SQRT_2;
+use std::fmt;
+use std::sync::Arc;
+
+const OUTLINE_DIRECTIONS: [(f32, f32); 8] = [
+    (-1.0, 0.0),
+    (1.0, 0.0),
+    (0.0, -1.0),
+    (0.0, 1.0),
+    (-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    (FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    (-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    (FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+];
 
 pub trait TextRenderer {
     type Error;
@@ -16,25 +32,91 @@ pub trait TextRenderer {
         variant: FontVariant,
     ) -> Result<(), Self::Error>;
 
-    fn render_layout(&mut self, layout: &TextLayout) -> Result<(), Self::Error> {
-        for glyph in &layout.glyphs {
-            let (r, g, b) = if glyph.is_shadow {
-                glyph.color.shadow_rgb()
-            } else {
-                glyph.color.rgb()
-            };
+    fn render_positioned_glyph(
+        &mut self,
+        glyph: &PositionedGlyph,
+        color: (u8, u8, u8, u8),
+    ) -> Result<(), Self::Error> {
+        self.render_glyph(glyph.ch, glyph.x, glyph.y, glyph.size, color, glyph.variant)
+    }
+
+    fn render_rect(
+        &mut self,
+        _x: f32,
+        _y: f32,
+        _width: f32,
+        _height: f32,
+        _color: (u8, u8, u8, u8),
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
 
-            self.render_glyph(
-                glyph.ch,
-                glyph.x,
-                glyph.y,
-                glyph.size,
+    fn render_decorations(&mut self, layout: &TextLayout, shadow: bool) -> Result<(), Self::Error> {
+        for decoration in layout.decorations.iter().filter(|d| d.is_shadow == shadow) {
+            let (r, g, b) = decoration.color.rgb();
+            self.render_rect(
+                decoration.x,
+                decoration.y,
+                decoration.width,
+                decoration.thickness,
                 (r, g, b, 255),
-                glyph.variant,
             )?;
         }
         Ok(())
     }
+
+    fn render_glyphs(&mut self, layout: &TextLayout, shadow: bool) -> Result<(), Self::Error> {
+        for glyph in layout.glyphs.iter().filter(|g| g.is_shadow == shadow) {
+            let (r, g, b) = glyph.color.rgb();
+            self.render_positioned_glyph(glyph, (r, g, b, 255))?;
+        }
+        Ok(())
+    }
+
+    fn render_outline(&mut self, layout: &TextLayout) -> Result<(), Self::Error> {
+        let Some((color, width)) = layout.outline else {
+            return Ok(());
+        };
+        let (r, g, b) = color.rgb();
+        for decoration in layout.decorations.iter().filter(|d| !d.is_shadow) {
+            self.render_rect(
+                decoration.x - width,
+                decoration.y - width,
+                decoration.width + width * 2.0,
+                decoration.thickness + width * 2.0,
+                (r, g, b, 255),
+            )?;
+        }
+        for glyph in layout.glyphs.iter().filter(|g| !g.is_shadow) {
+            for (dx, dy) in OUTLINE_DIRECTIONS {
+                let offset = PositionedGlyph {
+                    x: glyph.x + dx * width,
+                    y: glyph.y + dy * width,
+                    ..glyph.clone()
+                };
+                self.render_positioned_glyph(&offset, (r, g, b, 255))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_layout(&mut self, layout: &TextLayout) -> Result<(), Self::Error> {
+        for background in &layout.backgrounds {
+            let (r, g, b) = background.color.rgb();
+            self.render_rect(
+                background.x,
+                background.y,
+                background.width,
+                background.height,
+                (r, g, b, background.alpha),
+            )?;
+        }
+        self.render_decorations(layout, true)?;
+        self.render_glyphs(layout, true)?;
+        self.render_outline(layout)?;
+        self.render_glyphs(layout, false)?;
+        self.render_decorations(layout, false)
+    }
 }
 
 pub struct TextRenderContext<'a> {
@@ -48,10 +130,18 @@ impl<'a> TextRenderContext<'a> {
         }
     }
 
+    pub fn layout(&self, text: &MCText, options: &LayoutOptions) -> TextLayout {
+        self.layout_engine.layout(text, options)
+    }
+
     pub fn layout_at(&self, text: &MCText, x: f32, y: f32, options: &LayoutOptions) -> TextLayout {
         self.layout_engine.layout_at(text, x, y, options)
     }
 
+    pub fn measure(&self, text: &MCText, options: &LayoutOptions) -> TextBounds {
+        self.layout(text, options).bounds()
+    }
+
     pub fn render<R: TextRenderer>(
         &self,
         renderer: &mut R,
@@ -59,9 +149,10 @@ impl<'a> TextRenderContext<'a> {
         x: f32,
         y: f32,
         options: &LayoutOptions,
-    ) -> Result<(), R::Error> {
+    ) -> Result<TextBounds, R::Error> {
         let layout = self.layout_at(text, x, y, options);
-        renderer.render_layout(&layout)
+        renderer.render_layout(&layout)?;
+        Ok(layout.bounds())
     }
 
     pub fn render_str<R: TextRenderer>(
@@ -71,12 +162,120 @@ impl<'a> TextRenderContext<'a> {
         x: f32,
         y: f32,
         options: &LayoutOptions,
-    ) -> Result<(), R::Error> {
+    ) -> Result<TextBounds, R::Error> {
         let parsed = MCText::parse(text);
         self.render(renderer, &parsed, x, y, options)
     }
+
+    pub fn rasterize(
+        &self,
+        text: &MCText,
+        options: &LayoutOptions,
+    ) -> Vec<(PositionedGlyph, RasterizedGlyph)> {
+        let font_system = self.layout_engine.font_system();
+        self.layout(text, options)
+            .glyphs
+            .into_iter()
+            .filter(|glyph| glyph.ch != ' ' && !glyph.ch.is_control())
+            .map(|glyph| {
+                let raster = RasterizedGlyph::new(font_system, &glyph);
+                (glyph, raster)
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "image")]
+    pub fn render_to_image(&self, text: &MCText, options: &LayoutOptions) -> image::RgbaImage {
+        let (dx, dy) = if options.shadow {
+            options.shadow_offset()
+        } else {
+            (0.0, 0.0)
+        };
+        let pad = options.outline.map_or(0.0, |(_, width)| width);
+        let layout = self.layout_at(text, (-dx).max(0.0) + pad, (-dy).max(0.0) + pad, options);
+        let width = (layout.width + dx.abs() + pad * 2.0).ceil() as u32;
+        let height = (layout.height + dy.abs() + pad * 2.0).ceil() as u32;
+
+        let mut renderer = SoftwareRenderer::with_size(
+            self.layout_engine.font_system(),
+            width as usize,
+            height as usize,
+        );
+        let _ = renderer.render_layout(&layout);
+        image::RgbaImage::from_raw(width, height, renderer.into_buffer())
+            .unwrap_or_else(|| image::RgbaImage::new(width, height))
+    }
+
+    #[cfg(feature = "rayon")]
+    pub fn render_lines_parallel(
+        &self,
+        renderer: &mut SoftwareRenderer<'_>,
+        lines: &[(MCText, f32, f32)],
+        options: &LayoutOptions,
+    ) -> Vec<TextBounds> {
+        use rayon::prelude::*;
+
+        let (width, height) = (renderer.width, renderer.height);
+        let font_system = renderer.font_system;
+        let layouts: Vec<TextLayout> = lines
+            .iter()
+            .map(|(text, x, y)| self.layout_at(text, *x, *y, options))
+            .collect();
+        let bounds = layouts.iter().map(TextLayout::bounds).collect();
+
+        let mut extents: Vec<(usize, usize, usize)> = layouts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, layout)| {
+                let (top, bottom) = vertical_extent(font_system, layout)?;
+                let top = top.floor().clamp(0.0, height as f32) as usize;
+                let bottom = (bottom.ceil() + 1.0).clamp(0.0, height as f32) as usize;
+                Some((top, bottom, index))
+            })
+            .collect();
+        extents.sort_by_key(|&(top, _, _)| top);
+
+        let mut groups: Vec<(usize, usize, Vec<usize>)> = Vec::new();
+        for (top, bottom, index) in extents {
+            match groups.last_mut() {
+                Some((_, end, members)) if top < *end => {
+                    *end = (*end).max(bottom);
+                    members.push(index);
+                }
+                _ => groups.push((top, bottom.max(top), vec![index])),
+            }
+        }
+
+        let gamma_correct = renderer.gamma_correct;
+        let mut rest = renderer.buffer.as_mut_slice();
+        let mut offset = 0;
+        let mut bands = Vec::with_capacity(groups.len());
+        for (top, bottom, members) in groups {
+            let (_, tail) = rest.split_at_mut((top - offset) * width * 4);
+            let (band, tail) = tail.split_at_mut((bottom - top) * width * 4);
+            bands.push((top, bottom, members, band));
+            rest = tail;
+            offset = bottom;
+        }
+
+        bands
+            .into_par_iter()
+            .for_each(|(top, bottom, members, band)| {
+                let mut band_renderer =
+                    SoftwareRenderer::new(font_system, band, width, bottom - top)
+                        .with_gamma_correct_blending(gamma_correct);
+                for index in members {
+                    let mut layout = layouts[index].clone();
+                    layout.translate(0.0, -(top as f32));
+                    let _ = band_renderer.render_layout(&layout);
+                }
+            });
+
+        bounds
+    }
 }
 
+#[derive(Debug, Clone)]
 pub struct RasterizedGlyph {
     pub bitmap: Vec<u8>,
     pub width: usize,
@@ -85,11 +284,155 @@ pub struct RasterizedGlyph {
     pub offset_y: i32,
 }
 
+impl RasterizedGlyph {
+    fn new(font_system: &FontSystem, glyph: &PositionedGlyph) -> Self {
+        let (metrics, bitmap) = rasterize_positioned(font_system, glyph);
+        let (metrics, bitmap) = match synthetic_bold_offset(font_system, glyph) {
+            Some(offset) => embolden(&metrics, &bitmap, offset.round() as usize),
+            None => (metrics, bitmap.to_vec()),
+        };
+        Self {
+            bitmap,
+            width: metrics.width,
+            height: metrics.height,
+            offset_x: metrics.xmin,
+            offset_y: -(metrics.height as i32) - metrics.ymin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderError {
+    BufferSize { expected: usize, actual: usize },
+    InvalidSize,
+    InvalidMaxWidth,
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::BufferSize { expected, actual } => write!(
+                f,
+                "buffer has {} bytes, expected {} (width * height * 4)",
+                actual, expected
+            ),
+            RenderError::InvalidSize => write!(f, "font size must be a positive finite number"),
+            RenderError::InvalidMaxWidth => {
+                write!(f, "max width must be a positive finite number")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+pub fn blend_over(dst: &mut [u8], color: (u8, u8, u8, u8)) {
+    let src_alpha = color.3 as u32;
+    if src_alpha == 0 || dst.len() < 4 {
+        return;
+    }
+
+    let dst_alpha = dst[3] as u32;
+    let out_alpha = src_alpha + dst_alpha * (255 - src_alpha) / 255;
+
+    if out_alpha == 0 {
+        return;
+    }
+
+    let blend = |src: u8, dst: u8| -> u8 {
+        let src = src as u32;
+        let dst = dst as u32;
+        ((src * src_alpha + dst * dst_alpha * (255 - src_alpha) / 255) / out_alpha) as u8
+    };
+
+    dst[0] = blend(color.0, dst[0]);
+    dst[1] = blend(color.1, dst[1]);
+    dst[2] = blend(color.2, dst[2]);
+    dst[3] = out_alpha as u8;
+}
+
+pub fn blend_image_over(
+    dst: &mut [u8],
+    dst_width: usize,
+    src: &[u8],
+    src_width: usize,
+    src_height: usize,
+    x: i32,
+    y: i32,
+) {
+    if dst_width == 0 {
+        return;
+    }
+    let dst_height = dst.len() / 4 / dst_width;
+
+    for row in 0..src_height {
+        let dy = y + row as i32;
+        if dy < 0 || dy as usize >= dst_height {
+            continue;
+        }
+        for col in 0..src_width {
+            let dx = x + col as i32;
+            if dx < 0 || dx as usize >= dst_width {
+                continue;
+            }
+            let s = (row * src_width + col) * 4;
+            let Some(pixel) = src.get(s..s + 4) else {
+                return;
+            };
+            let d = (dy as usize * dst_width + dx as usize) * 4;
+            blend_over(&mut dst[d..d + 4], (pixel[0], pixel[1], pixel[2], pixel[3]));
+        }
+    }
+}
+
+pub fn blend_over_linear(dst: &mut [u8], color: (u8, u8, u8, u8)) {
+    if color.3 == 0 || dst.len() < 4 {
+        return;
+    }
+
+    let src_alpha = color.3 as f32 / 255.0;
+    let dst_alpha = dst[3] as f32 / 255.0;
+    let out_alpha = src_alpha + dst_alpha * (1.0 - src_alpha);
+
+    let blend = |src: u8, dst: u8| -> u8 {
+        let src = srgb_to_linear(src);
+        let dst = srgb_to_linear(dst);
+        linear_to_srgb((src * src_alpha + dst * dst_alpha * (1.0 - src_alpha)) / out_alpha)
+    };
+
+    dst[0] = blend(color.0, dst[0]);
+    dst[1] = blend(color.1, dst[1]);
+    dst[2] = blend(color.2, dst[2]);
+    dst[3] = (out_alpha * 255.0).round() as u8;
+}
+
+enum Canvas<'a> {
+    Borrowed(&'a mut [u8]),
+    Owned(Vec<u8>),
+}
+
+impl Canvas<'_> {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Canvas::Borrowed(buffer) => buffer,
+            Canvas::Owned(buffer) => buffer,
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        match self {
+            Canvas::Borrowed(buffer) => buffer,
+            Canvas::Owned(buffer) => buffer,
+        }
+    }
+}
+
 pub struct SoftwareRenderer<'a> {
     font_system: &'a FontSystem,
-    buffer: &'a mut [u8],
+    buffer: Canvas<'a>,
     width: usize,
     height: usize,
+    gamma_correct: bool,
 }
 
 impl<'a> SoftwareRenderer<'a> {
@@ -106,9 +449,52 @@ impl<'a> SoftwareRenderer<'a> {
         );
         Self {
             font_system,
-            buffer,
+            buffer: Canvas::Borrowed(buffer),
+            width,
+            height,
+            gamma_correct: false,
+        }
+    }
+
+    pub fn with_buffer(
+        font_system: &'a FontSystem,
+        buffer: &'a mut [u8],
+        width: usize,
+        height: usize,
+    ) -> Result<Self, RenderError> {
+        let expected = width * height * 4;
+        if buffer.len() != expected {
+            return Err(RenderError::BufferSize {
+                expected,
+                actual: buffer.len(),
+            });
+        }
+        Ok(Self::new(font_system, buffer, width, height))
+    }
+
+    pub fn with_size(font_system: &'a FontSystem, width: usize, height: usize) -> Self {
+        Self {
+            font_system,
+            buffer: Canvas::Owned(vec![0u8; width * height * 4]),
             width,
             height,
+            gamma_correct: false,
+        }
+    }
+
+    pub fn with_gamma_correct_blending(mut self, enabled: bool) -> Self {
+        self.gamma_correct = enabled;
+        self
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        self.buffer.as_slice()
+    }
+
+    pub fn into_buffer(self) -> Vec<u8> {
+        match self.buffer {
+            Canvas::Borrowed(buffer) => buffer.to_vec(),
+            Canvas::Owned(buffer) => buffer,
         }
     }
 
@@ -118,32 +504,45 @@ impl<'a> SoftwareRenderer<'a> {
         }
 
         let idx = (y * self.width + x) * 4;
-        if idx + 3 >= self.buffer.len() {
+        let Some(pixel) = self.buffer.as_mut_slice().get_mut(idx..idx + 4) else {
             return;
-        }
+        };
 
         let src_alpha = (alpha as u32 * color.3 as u32) / 255;
-        if src_alpha == 0 {
-            return;
+        let color = (color.0, color.1, color.2, src_alpha as u8);
+        if self.gamma_correct {
+            blend_over_linear(pixel, color);
+        } else {
+            blend_over(pixel, color);
         }
+    }
 
-        let dst_alpha = self.buffer[idx + 3] as u32;
-        let out_alpha = src_alpha + dst_alpha * (255 - src_alpha) / 255;
+    fn draw_bitmap(
+        &mut self,
+        metrics: &GlyphMetrics,
+        bitmap: &[u8],
+        x: f32,
+        y: f32,
+        color: (u8, u8, u8, u8),
+    ) {
+        let gx = (x + metrics.xmin as f32) as i32;
+        let gy = (y - metrics.height as f32 - metrics.ymin as f32) as i32;
 
-        if out_alpha == 0 {
-            return;
-        }
+        for row in 0..metrics.height {
+            for col in 0..metrics.width {
+                let px = gx + col as i32;
+                let py = gy + row as i32;
 
-        let blend = |src: u8, dst: u8| -> u8 {
-            let src = src as u32;
-            let dst = dst as u32;
-            ((src * src_alpha + dst * dst_alpha * (255 - src_alpha) / 255) / out_alpha) as u8
-        };
+                if px < 0 || py < 0 {
+                    continue;
+                }
 
-        self.buffer[idx] = blend(color.0, self.buffer[idx]);
-        self.buffer[idx + 1] = blend(color.1, self.buffer[idx + 1]);
-        self.buffer[idx + 2] = blend(color.2, self.buffer[idx + 2]);
-        self.buffer[idx + 3] = out_alpha as u8;
+                let alpha = bitmap[row * metrics.width + col];
+                if alpha > 0 {
+                    self.blend_pixel(px as usize, py as usize, color, alpha);
+                }
+            }
+        }
     }
 }
 
@@ -164,23 +563,44 @@ impl TextRenderer for SoftwareRenderer<'_> {
         }
 
         let (metrics, bitmap) = self.font_system.rasterize(ch, size, variant);
+        self.draw_bitmap(&metrics, &bitmap, x, y, color);
+        Ok(())
+    }
 
-        let gx = (x + metrics.xmin as f32) as i32;
-        let gy = (y - metrics.height as f32 - metrics.ymin as f32) as i32;
+    fn render_positioned_glyph(
+        &mut self,
+        glyph: &PositionedGlyph,
+        color: (u8, u8, u8, u8),
+    ) -> Result<(), Self::Error> {
+        if glyph.ch == ' ' || glyph.ch.is_control() {
+            return Ok(());
+        }
 
-        for row in 0..metrics.height {
-            for col in 0..metrics.width {
-                let px = gx + col as i32;
-                let py = gy + row as i32;
+        let (metrics, bitmap) = rasterize_positioned(self.font_system, glyph);
+        self.draw_bitmap(&metrics, &bitmap, glyph.x, glyph.y, color);
 
-                if px < 0 || py < 0 {
-                    continue;
-                }
+        if let Some(offset) = synthetic_bold_offset(self.font_system, glyph) {
+            self.draw_bitmap(&metrics, &bitmap, glyph.x + offset, glyph.y, color);
+        }
+        Ok(())
+    }
 
-                let alpha = bitmap[row * metrics.width + col];
-                if alpha > 0 {
-                    self.blend_pixel(px as usize, py as usize, color, alpha);
-                }
+    fn render_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        color: (u8, u8, u8, u8),
+    ) -> Result<(), Self::Error> {
+        let x0 = x.round().max(0.0) as usize;
+        let y0 = y.round().max(0.0) as usize;
+        let x1 = (x + width).round().max(0.0) as usize;
+        let y1 = (y + height.max(1.0)).round().max(0.0) as usize;
+
+        for py in y0..y1.min(self.height) {
+            for px in x0..x1.min(self.width) {
+                self.blend_pixel(px, py, color, 255);
             }
         }
 
@@ -188,6 +608,103 @@ impl TextRenderer for SoftwareRenderer<'_> {
     }
 }
 
+#[cfg(feature = "rayon")]
+fn vertical_extent(font_system: &FontSystem, layout: &TextLayout) -> Option<(f32, f32)> {
+    let glyphs = layout
+        .glyphs
+        .iter()
+        .filter(|glyph| glyph.ch != ' ' && !glyph.ch.is_control())
+        .map(|glyph| {
+            let (metrics, _) = rasterize_positioned(font_system, glyph);
+            let bottom = glyph.y - metrics.ymin as f32;
+            (bottom - metrics.height as f32, bottom)
+        });
+    let decorations = layout
+        .decorations
+        .iter()
+        .map(|decoration| (decoration.y, decoration.y + decoration.thickness));
+    let backgrounds = layout
+        .backgrounds
+        .iter()
+        .map(|background| (background.y, background.y + background.height));
+
+    let (top, bottom) = glyphs
+        .chain(decorations)
+        .chain(backgrounds)
+        .reduce(|(top, bottom), (t, b)| (top.min(t), bottom.max(b)))?;
+    let pad = layout.outline.map_or(0.0, |(_, width)| width);
+    Some((top - pad, bottom + pad))
+}
+
+fn rasterize_positioned(
+    font_system: &FontSystem,
+    glyph: &PositionedGlyph,
+) -> (GlyphMetrics, Arc<[u8]>) {
+    let scale = glyph.pixel_scale.unwrap_or(1).max(1);
+    let raster_size = glyph.size / scale as f32;
+    let (metrics, bitmap) = match &glyph.custom_font {
+        Some(name) => font_system.rasterize_named(glyph.ch, raster_size, name),
+        None if glyph.family != FontFamily::Minecraft => {
+            font_system.rasterize_family(glyph.ch, raster_size, glyph.family)
+        }
+        None => font_system.rasterize(glyph.ch, raster_size, glyph.variant),
+    };
+
+    match glyph.pixel_scale {
+        Some(_) => {
+            let (metrics, bitmap) = pixelate(&metrics, &bitmap, scale as usize);
+            (metrics, bitmap.into())
+        }
+        None => (metrics, bitmap),
+    }
+}
+
+fn synthetic_bold_offset(font_system: &FontSystem, glyph: &PositionedGlyph) -> Option<f32> {
+    let synthetic =
+        glyph.custom_font.is_none() && glyph.variant.is_bold() && !glyph.family.supports_styles();
+    synthetic.then(|| font_system.synthetic_bold_offset(glyph.size))
+}
+
+fn embolden(metrics: &GlyphMetrics, bitmap: &[u8], shift: usize) -> (GlyphMetrics, Vec<u8>) {
+    let width = metrics.width + shift;
+    let mut bold = vec![0u8; width * metrics.height];
+
+    for row in 0..metrics.height {
+        for col in 0..metrics.width {
+            let alpha = bitmap[row * metrics.width + col];
+            for target in [col, col + shift] {
+                let pixel = &mut bold[row * width + target];
+                *pixel = (*pixel).max(alpha);
+            }
+        }
+    }
+
+    let bold_metrics = GlyphMetrics { width, ..*metrics };
+    (bold_metrics, bold)
+}
+
+fn pixelate(metrics: &GlyphMetrics, bitmap: &[u8], scale: usize) -> (GlyphMetrics, Vec<u8>) {
+    let width = metrics.width * scale;
+    let height = metrics.height * scale;
+    let mut scaled = vec![0u8; width * height];
+
+    for row in 0..height {
+        for col in 0..width {
+            let alpha = bitmap[(row / scale) * metrics.width + col / scale];
+            scaled[row * width + col] = if alpha >= 128 { 255 } else { 0 };
+        }
+    }
+
+    let scaled_metrics = GlyphMetrics {
+        advance_width: metrics.advance_width * scale as f32,
+        width,
+        height,
+        xmin: metrics.xmin * scale as i32,
+        ymin: metrics.ymin * scale as i32,
+    };
+    (scaled_metrics, scaled)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,11 +722,260 @@ mod tests {
         {
             let mut renderer = SoftwareRenderer::new(&system, &mut buffer, width, height);
             let ctx = TextRenderContext::new(&system);
-            ctx.render_str(&mut renderer, "Hi", 10.0, 10.0, &LayoutOptions::new(16.0))
+            let options = LayoutOptions::new(16.0);
+            let bounds = ctx
+                .render_str(&mut renderer, "Hi", 10.0, 10.0, &options)
                 .unwrap();
+            let measured = ctx.measure(&MCText::parse("Hi"), &options);
+            assert_eq!(bounds.width, measured.width);
+            assert_eq!(bounds.line_count, 1);
+            assert_eq!(bounds.baseline, measured.baseline + 10.0);
         }
 
         let has_content = buffer.chunks(4).any(|p| p[0] + p[1] + p[2] > 0);
         assert!(has_content);
     }
+
+    #[test]
+    fn test_render_uses_span_color() {
+        use crate::color::NamedColor;
+
+        let system = FontSystem::modern();
+        let (width, height) = (100, 50);
+        let mut buffer = vec![0u8; width * height * 4];
+
+        {
+            let mut renderer = SoftwareRenderer::new(&system, &mut buffer, width, height);
+            let ctx = TextRenderContext::new(&system);
+            let text = MCText::new().span("Hi").color(NamedColor::Red).build();
+            let options = LayoutOptions::new(16.0).with_shadow(false);
+            ctx.render(&mut renderer, &text, 10.0, 10.0, &options)
+                .unwrap();
+        }
+
+        let drawn: Vec<_> = buffer.chunks(4).filter(|p| p[3] > 0).collect();
+        assert!(!drawn.is_empty());
+        assert!(drawn.iter().all(|p| (p[0], p[1], p[2]) == (255, 85, 85)));
+    }
+
+    #[test]
+    fn test_renderer_buffers() {
+        let system = FontSystem::modern();
+        let mut small = vec![0u8; 10];
+        assert_eq!(
+            SoftwareRenderer::with_buffer(&system, &mut small, 2, 2).err(),
+            Some(RenderError::BufferSize {
+                expected: 16,
+                actual: 10
+            })
+        );
+
+        let mut renderer = SoftwareRenderer::with_size(&system, 100, 50);
+        let ctx = TextRenderContext::new(&system);
+        ctx.render_str(&mut renderer, "Hi", 10.0, 10.0, &LayoutOptions::new(16.0))
+            .unwrap();
+        assert!(renderer.into_buffer().chunks(4).any(|p| p[3] > 0));
+
+        let mut pixel = [0, 0, 0, 255];
+        blend_over(&mut pixel, (255, 255, 255, 128));
+        assert_eq!(pixel, [128, 128, 128, 255]);
+
+        let mut pixel = [0, 0, 0, 255];
+        blend_over_linear(&mut pixel, (255, 255, 255, 128));
+        assert_eq!(pixel, [188, 188, 188, 255]);
+
+        let mut pixel = [10, 20, 30, 0];
+        blend_over_linear(&mut pixel, (200, 100, 50, 255));
+        assert_eq!(pixel, [200, 100, 50, 255]);
+    }
+
+    #[test]
+    fn test_blend_image_over() {
+        let mut dst = [0, 0, 0, 255].repeat(9);
+        let src = [
+            [255, 0, 0, 255],
+            [0, 255, 0, 128],
+            [0, 0, 255, 0],
+            [9, 9, 9, 255],
+        ]
+        .concat();
+        blend_image_over(&mut dst, 3, &src, 2, 2, -1, 2);
+
+        let pixel = |x: usize, y: usize| &dst[(y * 3 + x) * 4..][..4];
+        assert_eq!(pixel(0, 2), [0, 128, 0, 255]);
+        assert_eq!(pixel(1, 2), [0, 0, 0, 255]);
+        assert_eq!(pixel(0, 0), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_render_lines_parallel() {
+        let system = FontSystem::modern();
+        let (width, height) = (120, 80);
+        let lines: Vec<_> = ["one", "two", "three"]
+            .iter()
+            .enumerate()
+            .map(|(i, line)| (MCText::parse(line), 4.0, 4.0 + i as f32 * 24.0))
+            .collect();
+        let options = LayoutOptions::new(16.0);
+
+        let mut parallel = vec![0u8; width * height * 4];
+        let bounds = {
+            let mut renderer = SoftwareRenderer::new(&system, &mut parallel, width, height);
+            TextRenderContext::new(&system).render_lines_parallel(&mut renderer, &lines, &options)
+        };
+
+        let mut sequential = vec![0u8; width * height * 4];
+        {
+            let mut renderer = SoftwareRenderer::new(&system, &mut sequential, width, height);
+            let ctx = TextRenderContext::new(&system);
+            for (text, x, y) in &lines {
+                ctx.render(&mut renderer, text, *x, *y, &options).unwrap();
+            }
+        }
+
+        assert_eq!(bounds.len(), 3);
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_render_lines_parallel_anchored() {
+        use crate::color::NamedColor;
+        use crate::layout::VerticalAnchor;
+
+        let system = FontSystem::modern();
+        let (width, height) = (160, 120);
+        let lines: Vec<_> = ["§nfirst", "second"]
+            .iter()
+            .enumerate()
+            .map(|(i, line)| (MCText::parse(line), 8.0, 50.0 + i as f32 * 50.0))
+            .collect();
+        let options = LayoutOptions::new(16.0)
+            .with_vertical_anchor(VerticalAnchor::Bottom)
+            .with_shadow_offset(3.0, 6.0)
+            .with_outline(NamedColor::Black, 2.0)
+            .with_background(NamedColor::DarkGray, 4.0);
+        let ctx = TextRenderContext::new(&system);
+
+        let mut parallel = vec![0u8; width * height * 4];
+        {
+            let mut renderer = SoftwareRenderer::new(&system, &mut parallel, width, height);
+            ctx.render_lines_parallel(&mut renderer, &lines, &options);
+        }
+
+        let mut sequential = vec![0u8; width * height * 4];
+        {
+            let mut renderer = SoftwareRenderer::new(&system, &mut sequential, width, height);
+            for (text, x, y) in &lines {
+                ctx.render(&mut renderer, text, *x, *y, &options).unwrap();
+            }
+        }
+
+        assert!(parallel.chunks(4).any(|pixel| pixel[3] > 0));
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn test_render_to_image() {
+        let system = FontSystem::modern();
+        let ctx = TextRenderContext::new(&system);
+        let text = MCText::parse("Hi");
+        let options = LayoutOptions::new(16.0);
+        let image = ctx.render_to_image(&text, &options);
+
+        let bounds = ctx.measure(&text, &options);
+        let (dx, dy) = options.shadow_offset();
+        assert_eq!(image.width(), (bounds.width + dx).ceil() as u32);
+        assert_eq!(image.height(), (bounds.height + dy).ceil() as u32);
+        assert!(image.pixels().any(|p| p.0 == [255, 255, 255, 255]));
+
+        let empty = ctx.render_to_image(&MCText::new(), &options.with_shadow(false));
+        assert!(empty.pixels().all(|p| p.0[3] == 0));
+    }
+
+    #[test]
+    fn test_render_outline() {
+        use crate::color::NamedColor;
+
+        let system = FontSystem::modern();
+        let ctx = TextRenderContext::new(&system);
+        let text = MCText::new()
+            .span("H")
+            .color(NamedColor::Red)
+            .then("i")
+            .color(NamedColor::Blue)
+            .build();
+        let draw = |options: &LayoutOptions| {
+            let mut renderer = SoftwareRenderer::with_size(&system, 60, 40);
+            ctx.render(&mut renderer, &text, 10.0, 10.0, options)
+                .unwrap();
+            renderer.into_buffer()
+        };
+        let plain = LayoutOptions::new(16.0).with_shadow(false);
+        let outlined = plain.clone().with_outline(NamedColor::Black, 1.0);
+
+        let opaque = |buffer: &[u8]| buffer.chunks(4).filter(|p| p[3] > 0).count();
+        let unfilled = draw(&plain);
+        let filled = draw(&outlined);
+        assert!(opaque(&filled) > opaque(&unfilled));
+        for (fill, outline) in unfilled.chunks(4).zip(filled.chunks(4)) {
+            if fill[3] == 255 {
+                assert_eq!(fill, outline);
+            }
+        }
+        assert!(filled.chunks(4).any(|p| p == [0, 0, 0, 255]));
+        assert_eq!(plain.with_outline(NamedColor::Black, 0.0).outline, None);
+    }
+
+    #[test]
+    fn test_rasterize_glyphs() {
+        let system = FontSystem::modern();
+        let ctx = TextRenderContext::new(&system);
+        let text = MCText::parse("A B");
+        let options = LayoutOptions::new(16.0);
+        let glyphs = ctx.rasterize(&text, &options);
+
+        assert_eq!(glyphs.len(), 4);
+        assert_eq!(glyphs.iter().filter(|(g, _)| g.is_shadow).count(), 2);
+        for (glyph, raster) in &glyphs {
+            assert!(glyph.ch != ' ');
+            assert_eq!(raster.bitmap.len(), raster.width * raster.height);
+            assert!(raster.offset_y < 0);
+        }
+
+        let mut renderer = SoftwareRenderer::with_size(&system, 60, 30);
+        ctx.render(&mut renderer, &text, 0.0, 0.0, &options)
+            .unwrap();
+        let (glyph, raster) = &glyphs[1];
+        let px = (glyph.x as i32 + raster.offset_x) as usize;
+        let py = (glyph.y as i32 + raster.offset_y) as usize;
+        let (col, row) = (0..raster.width * raster.height)
+            .map(|i| (i % raster.width, i / raster.width))
+            .find(|&(col, row)| raster.bitmap[row * raster.width + col] == 255)
+            .unwrap();
+        let index = ((py + row) * 60 + px + col) * 4;
+        assert_eq!(renderer.buffer()[index..index + 4], [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_render_pixel_scale() {
+        let system = FontSystem::modern();
+        let (width, height) = (100, 50);
+        let mut buffer = vec![0u8; width * height * 4];
+
+        {
+            let mut renderer = SoftwareRenderer::new(&system, &mut buffer, width, height);
+            let ctx = TextRenderContext::new(&system);
+            let options = LayoutOptions::new(24.0)
+                .with_shadow(false)
+                .with_pixel_scale(3);
+            ctx.render_str(&mut renderer, "Hi", 10.0, 10.0, &options)
+                .unwrap();
+        }
+
+        assert!(buffer.chunks(4).any(|p| p[3] == 255));
+        assert!(buffer.chunks(4).all(|p| p[3] == 0 || p[3] == 255));
+    }
 }
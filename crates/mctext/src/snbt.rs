@@ -0,0 +1,239 @@
+use crate::json::{ParseError, parse_value};
+use crate::text::MCText;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::Peekable;
+use core::str::CharIndices;
+use serde_json::{Map, Number, Value};
+
+pub fn parse_snbt_component(input: &str) -> Result<MCText, ParseError> {
+    let mut parser = Parser {
+        input,
+        chars: input.char_indices().peekable(),
+    };
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if let Some(&(index, _)) = parser.chars.peek() {
+        return Err(parser.error(index, "trailing characters"));
+    }
+    Ok(parse_value(&value))
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, index: usize, message: &str) -> ParseError {
+        ParseError::InvalidSnbt {
+            message: String::from(message),
+            at: index,
+        }
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map(|&(index, _)| index)
+            .unwrap_or(self.input.len())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, ch)| ch.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        let position = self.position();
+        match self.chars.next() {
+            Some((_, ch)) if ch == expected => Ok(()),
+            _ => Err(self.error(position, &format!("expected '{}'", expected))),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, ParseError> {
+        self.skip_whitespace();
+        let position = self.position();
+        match self.chars.peek().map(|&(_, ch)| ch) {
+            Some('{') => self.parse_compound(),
+            Some('[') => self.parse_list(),
+            Some(quote @ ('"' | '\'')) => {
+                self.chars.next();
+                self.parse_quoted(quote).map(Value::String)
+            }
+            Some(_) => {
+                let token = self.parse_unquoted();
+                if token.is_empty() {
+                    return Err(self.error(position, "expected value"));
+                }
+                Ok(unquoted_value(token))
+            }
+            None => Err(self.error(position, "unexpected end of input")),
+        }
+    }
+
+    fn parse_compound(&mut self) -> Result<Value, ParseError> {
+        self.expect('{')?;
+        let mut map = Map::new();
+
+        self.skip_whitespace();
+        if self.chars.next_if(|&(_, ch)| ch == '}').is_some() {
+            return Ok(Value::Object(map));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let position = self.position();
+            let key = match self.chars.peek().map(|&(_, ch)| ch) {
+                Some(quote @ ('"' | '\'')) => {
+                    self.chars.next();
+                    self.parse_quoted(quote)?
+                }
+                _ => String::from(self.parse_unquoted()),
+            };
+            if key.is_empty() {
+                return Err(self.error(position, "expected key"));
+            }
+
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            self.skip_whitespace();
+            let position = self.position();
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, '}')) => return Ok(Value::Object(map)),
+                _ => return Err(self.error(position, "expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Value, ParseError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+
+        let rest = &self.input[self.position()..];
+        if let Some(after) = rest.strip_prefix(['B', 'I', 'L']) {
+            if after.starts_with(';') {
+                self.chars.next();
+                self.chars.next();
+            }
+        }
+
+        self.skip_whitespace();
+        if self.chars.next_if(|&(_, ch)| ch == ']').is_some() {
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            items.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            let position = self.position();
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, ']')) => return Ok(Value::Array(items)),
+                _ => return Err(self.error(position, "expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_quoted(&mut self, quote: char) -> Result<String, ParseError> {
+        let mut result = String::new();
+
+        while let Some((index, ch)) = self.chars.next() {
+            match ch {
+                '\\' => match self.chars.next() {
+                    Some((_, 'n')) => result.push('\n'),
+                    Some((_, 't')) => result.push('\t'),
+                    Some((_, escaped)) => result.push(escaped),
+                    None => return Err(self.error(index, "unterminated escape")),
+                },
+                _ if ch == quote => return Ok(result),
+                _ => result.push(ch),
+            }
+        }
+
+        Err(self.error(self.input.len(), "unterminated string"))
+    }
+
+    fn parse_unquoted(&mut self) -> &'a str {
+        let start = self.position();
+        while self
+            .chars
+            .next_if(|&(_, ch)| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.' | '+'))
+            .is_some()
+        {}
+        &self.input[start..self.position()]
+    }
+}
+
+fn unquoted_value(token: &str) -> Value {
+    match token {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+
+    let (digits, suffix) = match token.char_indices().last() {
+        Some((index, ch)) if matches!(ch.to_ascii_lowercase(), 'b' | 's' | 'l' | 'f' | 'd') => {
+            (&token[..index], Some(ch.to_ascii_lowercase()))
+        }
+        _ => (token, None),
+    };
+
+    let number = match suffix {
+        Some('f' | 'd') => digits.parse::<f64>().ok().and_then(Number::from_f64),
+        Some(_) => digits.parse::<i64>().ok().map(Number::from),
+        None => digits.parse::<i64>().ok().map(Number::from).or_else(|| {
+            digits
+                .contains('.')
+                .then(|| digits.parse::<f64>().ok())
+                .flatten()
+                .and_then(Number::from_f64)
+        }),
+    };
+
+    match number {
+        Some(number) => Value::Number(number),
+        None => Value::String(String::from(token)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{NamedColor, TextColor};
+
+    #[test]
+    fn test_parse_snbt_component() {
+        let text = parse_snbt_component(
+            r#"{text:'Hello ',color:red,bold:1b,extra:[{"text":"it's",italic:true},"!"]}"#,
+        )
+        .unwrap();
+        let spans = text.spans();
+
+        assert_eq!(text.plain_text(), "Hello it's!");
+        assert_eq!(spans[0].color, Some(TextColor::Named(NamedColor::Red)));
+        assert!(spans[0].style.bold);
+        assert!(spans[1].style.italic && spans[1].style.bold);
+    }
+
+    #[test]
+    fn test_parse_snbt_errors() {
+        assert!(parse_snbt_component("{text:'unterminated}").is_err());
+        assert_eq!(
+            parse_snbt_component("{text:'a' bold:1b}")
+                .unwrap_err()
+                .offset(),
+            Some(10)
+        );
+        assert_eq!(
+            parse_snbt_component("'plain'").unwrap().plain_text(),
+            "plain"
+        );
+    }
+}
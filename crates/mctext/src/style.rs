@@ -79,6 +79,45 @@ impl Style {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OptStyle {
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+}
+
+impl OptStyle {
+    pub fn is_unset(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn apply(&self, parent: &Style) -> Style {
+        Style {
+            bold: self.bold.unwrap_or(parent.bold),
+            italic: self.italic.unwrap_or(parent.italic),
+            underlined: self.underlined.unwrap_or(parent.underlined),
+            strikethrough: self.strikethrough.unwrap_or(parent.strikethrough),
+            obfuscated: self.obfuscated.unwrap_or(parent.obfuscated),
+        }
+    }
+}
+
+impl From<Style> for OptStyle {
+    fn from(style: Style) -> Self {
+        let set = |flag: bool| flag.then_some(true);
+        OptStyle {
+            bold: set(style.bold),
+            italic: set(style.italic),
+            underlined: set(style.underlined),
+            strikethrough: set(style.strikethrough),
+            obfuscated: set(style.obfuscated),
+        }
+    }
+}
+
 pub fn is_format_code(code: char) -> bool {
     matches!(code.to_ascii_lowercase(), 'l' | 'o' | 'n' | 'm' | 'k' | 'r')
 }
@@ -97,4 +136,27 @@ mod tests {
         assert!(style.bold && style.italic && !style.underlined);
         assert_eq!(Style::from_code('l'), Some(Style::default().bold()));
     }
+
+    #[test]
+    fn test_inheritance() {
+        let parent = Style::default().bold().italic();
+        let child = Style::default().underlined();
+        assert_eq!(
+            Style::merge(&parent, &child),
+            Style::default().bold().italic().underlined()
+        );
+
+        let unset = OptStyle::default();
+        assert!(unset.is_unset());
+        assert_eq!(unset.apply(&parent), parent);
+
+        let cleared = OptStyle {
+            bold: Some(false),
+            ..OptStyle::from(child)
+        };
+        assert_eq!(
+            cleared.apply(&parent),
+            Style::default().italic().underlined()
+        );
+    }
 }
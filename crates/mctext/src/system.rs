@@ -1,6 +1,11 @@
 use crate::fonts::{FontFamily, FontVariant, FontVersion};
+use crate::layout::{LayoutEngine, LayoutOptions};
+use crate::text::{MCText, strip_codes};
+use crate::wrap::{TAB_SPACES, tab_advance};
 use fontdue::{Font, FontSettings, Metrics};
-use std::sync::OnceLock;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
 
 #[cfg(feature = "special-fonts")]
 use crate::fonts::{ENCHANTING_REGULAR, ILLAGER_REGULAR};
@@ -27,8 +32,51 @@ fn illager_font() -> &'static Font {
 }
 
 const SPACE_WIDTH_RATIO: f32 = 0.4;
+const MC_PIXEL_SIZE: f32 = 12.0;
+const SYNTHETIC_BOLD_RATIO: f32 = 1.0 / 12.0;
 const DEFAULT_ASCENT_RATIO: f32 = 0.8;
 
+type CachedGlyph = (GlyphMetrics, Arc<[u8]>);
+
+#[derive(Default)]
+struct GlyphCache {
+    variants: HashMap<(char, u32, FontVariant), CachedGlyph>,
+    families: HashMap<(char, u32, FontFamily), CachedGlyph>,
+    named: HashMap<String, HashMap<(char, u32), CachedGlyph>>,
+}
+
+impl GlyphCache {
+    fn len(&self) -> usize {
+        self.variants.len()
+            + self.families.len()
+            + self.named.values().map(HashMap::len).sum::<usize>()
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+fn cache_size(size: f32) -> u32 {
+    size.round().max(1.0) as u32
+}
+
+fn rasterize_cached<K: Eq + core::hash::Hash>(
+    cache: &Mutex<GlyphCache>,
+    map: impl Fn(&mut GlyphCache) -> &mut HashMap<K, CachedGlyph>,
+    key: K,
+    rasterize: impl FnOnce() -> (Metrics, Vec<u8>),
+) -> CachedGlyph {
+    if let Some(cached) = map(&mut cache.lock().unwrap()).get(&key) {
+        return cached.clone();
+    }
+    let (metrics, bitmap) = rasterize();
+    let glyph: CachedGlyph = (metrics.into(), bitmap.into());
+    map(&mut cache.lock().unwrap()).insert(key, glyph.clone());
+    glyph
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct GlyphMetrics {
     pub advance_width: f32,
     pub width: usize,
@@ -49,12 +97,64 @@ impl From<Metrics> for GlyphMetrics {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineMetrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+}
+
+impl LineMetrics {
+    fn fallback(size: f32) -> Self {
+        Self {
+            ascent: size * DEFAULT_ASCENT_RATIO,
+            descent: -size * (1.0 - DEFAULT_ASCENT_RATIO),
+            line_gap: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerticalMetrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+    pub x_height: f32,
+    pub cap_height: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontError {
+    InvalidFont(String),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::InvalidFont(msg) => write!(f, "invalid font: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
 pub struct FontSystem {
     version: FontVersion,
     regular: OnceLock<Font>,
     bold: OnceLock<Font>,
     italic: OnceLock<Font>,
     bold_italic: OnceLock<Font>,
+    custom_fonts: HashMap<String, Font>,
+    fallback_fonts: Vec<Font>,
+    glyph_cache: Mutex<GlyphCache>,
+}
+
+fn space_advance(font: &Font, size: f32) -> f32 {
+    if font.lookup_glyph_index(' ') != 0 {
+        font.metrics(' ', size).advance_width
+    } else {
+        size * SPACE_WIDTH_RATIO
+    }
 }
 
 impl FontSystem {
@@ -65,7 +165,105 @@ impl FontSystem {
             bold: OnceLock::new(),
             italic: OnceLock::new(),
             bold_italic: OnceLock::new(),
+            custom_fonts: HashMap::new(),
+            fallback_fonts: Vec::new(),
+            glyph_cache: Mutex::new(GlyphCache::default()),
+        }
+    }
+
+    pub fn with_fallback_font(mut self, data: Vec<u8>) -> Result<Self, FontError> {
+        self.add_fallback_font(data)?;
+        Ok(self)
+    }
+
+    pub fn add_fallback_font(&mut self, data: Vec<u8>) -> Result<(), FontError> {
+        let font = Font::from_bytes(data, FontSettings::default())
+            .map_err(|e| FontError::InvalidFont(e.to_string()))?;
+        self.fallback_fonts.push(font);
+        self.glyph_cache.get_mut().unwrap().clear();
+        Ok(())
+    }
+
+    pub fn has_glyph(&self, ch: char) -> bool {
+        self.font(FontVariant::Regular).lookup_glyph_index(ch) != 0
+            || self
+                .fallback_fonts
+                .iter()
+                .any(|font| font.lookup_glyph_index(ch) != 0)
+    }
+
+    pub fn has_glyph_in(&self, ch: char, family: FontFamily, variant: FontVariant) -> bool {
+        let font = match family {
+            FontFamily::Minecraft => self.resolve(ch, variant),
+            #[cfg(feature = "special-fonts")]
+            _ => self.font_for_family(family),
+        };
+        font.lookup_glyph_index(ch) != 0
+    }
+
+    pub fn missing_glyphs(
+        &self,
+        text: &str,
+        family: FontFamily,
+        variant: FontVariant,
+    ) -> Vec<char> {
+        let mut missing = Vec::new();
+        for ch in text.chars() {
+            if ch.is_whitespace() || ch.is_control() || missing.contains(&ch) {
+                continue;
+            }
+            if !self.has_glyph_in(ch, family, variant) {
+                missing.push(ch);
+            }
         }
+        missing
+    }
+
+    fn resolve(&self, ch: char, variant: FontVariant) -> &Font {
+        let primary = self.font(variant);
+        if primary.lookup_glyph_index(ch) != 0 {
+            return primary;
+        }
+        self.fallback_fonts
+            .iter()
+            .find(|font| font.lookup_glyph_index(ch) != 0)
+            .unwrap_or(primary)
+    }
+
+    pub fn kerning(&self, left: char, right: char, size: f32, variant: FontVariant) -> f32 {
+        let font = self.resolve(left, variant);
+        if !std::ptr::eq(font, self.resolve(right, variant)) {
+            return 0.0;
+        }
+        font.horizontal_kern(left, right, size).unwrap_or(0.0)
+    }
+
+    pub fn kerning_named(&self, left: char, right: char, size: f32, name: &str) -> f32 {
+        match self.custom_font(name) {
+            Some(font) => font.horizontal_kern(left, right, size).unwrap_or(0.0),
+            None => self.kerning(left, right, size, FontVariant::Regular),
+        }
+    }
+
+    pub fn with_custom_font(mut self, name: &str, data: Vec<u8>) -> Result<Self, FontError> {
+        self.add_custom_font(name, data)?;
+        Ok(self)
+    }
+
+    pub fn add_custom_font(&mut self, name: &str, data: Vec<u8>) -> Result<(), FontError> {
+        let font = Font::from_bytes(data, FontSettings::default())
+            .map_err(|e| FontError::InvalidFont(e.to_string()))?;
+        self.custom_fonts.insert(name.to_string(), font);
+        self.glyph_cache.get_mut().unwrap().named.remove(name);
+        Ok(())
+    }
+
+    pub fn custom_font(&self, name: &str) -> Option<&Font> {
+        self.custom_fonts.get(name)
+    }
+
+    pub fn has_custom_font(&self, name: &str) -> bool {
+        self.custom_fonts.contains_key(name)
     }
 
     fn load_font(&self, variant: FontVariant) -> Font {
@@ -94,7 +292,7 @@ impl FontSystem {
     }
 
     fn metrics(&self, ch: char, size: f32, variant: FontVariant) -> GlyphMetrics {
-        self.font(variant).metrics(ch, size).into()
+        self.resolve(ch, variant).metrics(ch, size).into()
     }
 
     pub fn font_for_family(&self, family: FontFamily) -> &Font {
@@ -107,9 +305,27 @@ impl FontSystem {
         }
     }
 
-    pub fn rasterize(&self, ch: char, size: f32, variant: FontVariant) -> (GlyphMetrics, Vec<u8>) {
-        let (metrics, bitmap) = self.font(variant).rasterize(ch, size);
-        (metrics.into(), bitmap)
+    pub fn rasterize(
+        &self,
+        ch: char,
+        size: f32,
+        variant: FontVariant,
+    ) -> (GlyphMetrics, Arc<[u8]>) {
+        let size = cache_size(size);
+        rasterize_cached(
+            &self.glyph_cache,
+            |cache| &mut cache.variants,
+            (ch, size, variant),
+            || self.resolve(ch, variant).rasterize(ch, size as f32),
+        )
+    }
+
+    pub fn clear_glyph_cache(&self) {
+        self.glyph_cache.lock().unwrap().clear();
+    }
+
+    pub fn glyph_cache_len(&self) -> usize {
+        self.glyph_cache.lock().unwrap().len()
     }
 
     pub fn rasterize_family(
@@ -117,41 +333,168 @@ impl FontSystem {
         ch: char,
         size: f32,
         family: FontFamily,
-    ) -> (GlyphMetrics, Vec<u8>) {
-        let (metrics, bitmap) = self.font_for_family(family).rasterize(ch, size);
-        (metrics.into(), bitmap)
+    ) -> (GlyphMetrics, Arc<[u8]>) {
+        let size = cache_size(size);
+        rasterize_cached(
+            &self.glyph_cache,
+            |cache| &mut cache.families,
+            (ch, size, family),
+            || self.font_for_family(family).rasterize(ch, size as f32),
+        )
     }
 
-    pub fn ascent_ratio(&self, variant: FontVariant) -> f32 {
+    pub fn rasterize_named(&self, ch: char, size: f32, name: &str) -> (GlyphMetrics, Arc<[u8]>) {
+        let Some(font) = self.custom_font(name) else {
+            return self.rasterize(ch, size, FontVariant::Regular);
+        };
+        let size = cache_size(size);
+        rasterize_cached(
+            &self.glyph_cache,
+            |cache| {
+                if !cache.named.contains_key(name) {
+                    cache.named.insert(name.to_string(), HashMap::new());
+                }
+                cache.named.get_mut(name).unwrap()
+            },
+            (ch, size),
+            || font.rasterize(ch, size as f32),
+        )
+    }
+
+    pub fn ascent_ratio_named(&self, name: &str) -> f32 {
         let size = 16.0;
-        self.font(variant)
+        match self.custom_font(name) {
+            Some(font) => font
+                .horizontal_line_metrics(size)
+                .map(|m| m.ascent / size)
+                .unwrap_or(DEFAULT_ASCENT_RATIO),
+            None => self.ascent_ratio(FontVariant::Regular),
+        }
+    }
+
+    pub fn ascent_ratio_family(&self, family: FontFamily, variant: FontVariant) -> f32 {
+        if family == FontFamily::Minecraft {
+            return self.ascent_ratio(variant);
+        }
+        let size = 16.0;
+        self.font_for_family(family)
             .horizontal_line_metrics(size)
             .map(|m| m.ascent / size)
             .unwrap_or(DEFAULT_ASCENT_RATIO)
     }
 
+    pub fn ascent_ratio(&self, variant: FontVariant) -> f32 {
+        let size = 16.0;
+        self.line_metrics(size, variant).ascent / size
+    }
+
+    pub fn line_metrics(&self, size: f32, variant: FontVariant) -> LineMetrics {
+        self.font(variant)
+            .horizontal_line_metrics(size)
+            .map(|m| LineMetrics {
+                ascent: m.ascent,
+                descent: m.descent,
+                line_gap: m.line_gap,
+            })
+            .unwrap_or_else(|| LineMetrics::fallback(size))
+    }
+
+    pub fn vertical_metrics(&self, size: f32, variant: FontVariant) -> VerticalMetrics {
+        let line = self.line_metrics(size, variant);
+        let top = |ch: char| {
+            let bounds = self.resolve(ch, variant).metrics(ch, size).bounds;
+            bounds.ymin + bounds.height
+        };
+        VerticalMetrics {
+            ascent: line.ascent,
+            descent: line.descent,
+            line_gap: line.line_gap,
+            x_height: top('x'),
+            cap_height: top('H'),
+        }
+    }
+
     pub fn measure_char(&self, ch: char, size: f32, variant: FontVariant) -> f32 {
         if ch == ' ' {
-            size * SPACE_WIDTH_RATIO
+            space_advance(self.font(variant), size)
         } else {
             self.metrics(ch, size, variant).advance_width
         }
     }
 
     pub fn measure_char_family(&self, ch: char, size: f32, family: FontFamily) -> f32 {
+        let font = self.font_for_family(family);
         if ch == ' ' {
-            size * SPACE_WIDTH_RATIO
+            space_advance(font, size)
         } else {
-            self.font_for_family(family).metrics(ch, size).advance_width
+            font.metrics(ch, size).advance_width
         }
     }
 
+    pub fn measure_char_family_styled(
+        &self,
+        ch: char,
+        size: f32,
+        family: FontFamily,
+        variant: FontVariant,
+    ) -> f32 {
+        let advance = self.measure_char_family(ch, size, family);
+        if ch != ' ' && variant.is_bold() && !family.supports_styles() {
+            advance + self.synthetic_bold_offset(size)
+        } else {
+            advance
+        }
+    }
+
+    pub fn synthetic_bold_offset(&self, size: f32) -> f32 {
+        size * SYNTHETIC_BOLD_RATIO
+    }
+
+    pub fn measure_char_named(&self, ch: char, size: f32, name: &str) -> f32 {
+        match self.custom_font(name) {
+            Some(font) if ch == ' ' => space_advance(font, size),
+            Some(font) => font.metrics(ch, size).advance_width,
+            None => self.measure_char(ch, size, FontVariant::Regular),
+        }
+    }
+
+    pub fn tab_width(&self, size: f32) -> f32 {
+        self.measure_char(' ', size, FontVariant::Regular) * TAB_SPACES
+    }
+
+    pub fn line_height(&self, size: f32) -> f32 {
+        let metrics = self.line_metrics(size, FontVariant::Regular);
+        metrics.ascent - metrics.descent + metrics.line_gap
+    }
+
+    pub fn measure_mc_pixels(&self, text: &str) -> u32 {
+        MCText::parse(text)
+            .styled_chars()
+            .filter(|(ch, _, _)| !ch.is_control())
+            .map(|(ch, _, style)| self.mc_pixel_advance(ch, style.bold))
+            .sum()
+    }
+
+    pub(crate) fn mc_pixel_advance(&self, ch: char, bold: bool) -> u32 {
+        let advance = self.measure_char(ch, MC_PIXEL_SIZE, FontVariant::Regular);
+        advance.round() as u32 + bold as u32
+    }
+
     pub fn measure_text(&self, text: &str, size: f32) -> f32 {
         self.measure_text_styled(text, size, FontVariant::Regular)
     }
 
+    pub fn measure_text_spaced(&self, text: &str, size: f32, spacing: f32) -> f32 {
+        let plain = strip_codes(text);
+        let count = plain.chars().filter(|ch| !ch.is_control()).count();
+        let trailing = plain.chars().last().is_some_and(|ch| ch != '\t');
+        let boundaries = count.saturating_sub(trailing as usize);
+        self.measure_text(text, size) + spacing * boundaries as f32
+    }
+
     pub fn measure_text_styled(&self, text: &str, size: f32, variant: FontVariant) -> f32 {
         let mut width = 0.0;
+        let mut previous = None;
         let mut chars = text.chars().peekable();
 
         while let Some(ch) = chars.next() {
@@ -159,25 +502,91 @@ impl FontSystem {
                 chars.next();
                 continue;
             }
+            if ch == '\t' {
+                previous = None;
+                width += tab_advance(width, self.tab_width(size));
+                continue;
+            }
             if ch.is_control() {
+                previous = None;
                 continue;
             }
+            if let Some(previous) = previous {
+                width += self.kerning(previous, ch, size, variant);
+            }
             width += self.measure_char(ch, size, variant);
+            previous = (ch != ' ').then_some(ch);
         }
 
         width
     }
 
     pub fn measure_text_family(&self, text: &str, size: f32, family: FontFamily) -> f32 {
+        self.measure_text_family_styled(text, size, family, FontVariant::Regular)
+    }
+
+    pub fn measure_text_family_styled(
+        &self,
+        text: &str,
+        size: f32,
+        family: FontFamily,
+        variant: FontVariant,
+    ) -> f32 {
         let mut width = 0.0;
         for ch in text.chars() {
+            if ch == '\t' {
+                width += tab_advance(width, self.tab_width(size));
+                continue;
+            }
             if ch.is_control() {
                 continue;
             }
-            width += self.measure_char_family(ch, size, family);
+            width += self.measure_char_family_styled(ch, size, family, variant);
         }
         width
     }
+
+    pub fn measure_text_named(&self, text: &str, size: f32, name: &str) -> f32 {
+        let mut width = 0.0;
+        let mut previous = None;
+        let mut chars = text.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            if ch == '\u{00A7}' {
+                chars.next();
+                continue;
+            }
+            if ch == '\t' {
+                previous = None;
+                width += tab_advance(width, self.tab_width(size));
+                continue;
+            }
+            if ch.is_control() {
+                previous = None;
+                continue;
+            }
+            if let Some(previous) = previous {
+                width += self.kerning_named(previous, ch, size, name);
+            }
+            width += self.measure_char_named(ch, size, name);
+            previous = (ch != ' ').then_some(ch);
+        }
+
+        width
+    }
+
+    pub fn measure_block(&self, text: &str, size: f32, max_width: f32) -> (f32, f32) {
+        let text = MCText::parse(text);
+        if text.plain_text().is_empty() {
+            return (0.0, 0.0);
+        }
+
+        let options = LayoutOptions::new(size)
+            .with_max_width(max_width)
+            .with_shadow(false);
+        let layout = LayoutEngine::new(self).layout(&text, &options);
+        (layout.width, layout.height)
+    }
 }
 
 #[cfg(feature = "modern-fonts")]
@@ -187,23 +596,166 @@ impl Default for FontSystem {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "modern-fonts"))]
 mod tests {
     use super::*;
 
     #[test]
-    #[cfg(feature = "modern-fonts")]
+    fn test_measure_mc_pixels() {
+        let system = FontSystem::modern();
+        assert_eq!(system.measure_mc_pixels("Hello"), 24);
+        assert_eq!(system.measure_mc_pixels("§cHello world"), 24 + 4 + 27);
+        assert_eq!(system.measure_mc_pixels("§lHi"), 7 + 3);
+        assert_eq!(system.measure_mc_pixels(""), 0);
+    }
+
+    #[test]
     fn test_font_system() {
         let system = FontSystem::modern();
         assert!(system.measure_text("Hello", 16.0) > 0.0);
     }
 
     #[test]
-    #[cfg(feature = "modern-fonts")]
     fn test_measure_skips_color_codes() {
         let system = FontSystem::modern();
         let plain = system.measure_text("Hello", 16.0);
         let colored = system.measure_text("§6Hello", 16.0);
         assert!((plain - colored).abs() < 0.001);
     }
+
+    #[test]
+    #[cfg(feature = "legacy-fonts")]
+    fn test_space_width_follows_version() {
+        for system in [FontSystem::modern(), FontSystem::legacy()] {
+            let font = system.font(FontVariant::Regular);
+            let expected = font.metrics(' ', 16.0).advance_width;
+            assert_eq!(
+                system.measure_char(' ', 16.0, FontVariant::Regular),
+                expected
+            );
+            assert_eq!(system.tab_width(16.0), expected * TAB_SPACES);
+            assert!(system.line_height(16.0) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_measure_tabs() {
+        let system = FontSystem::modern();
+        let tab = system.tab_width(16.0);
+        let a = system.measure_char('a', 16.0, FontVariant::Regular);
+        assert_eq!(system.measure_text("\t", 16.0), tab);
+        assert_eq!(
+            system.measure_text("a\tb", 16.0),
+            tab + system.measure_text("b", 16.0)
+        );
+        assert!(a < tab);
+    }
+
+    #[test]
+    fn test_measure_block() {
+        let system = FontSystem::modern();
+        let line = system.measure_text("Hello", 16.0);
+        let (width, height) = system.measure_block("Hello Hello Hello", 16.0, line * 1.5);
+        let options = LayoutOptions::new(16.0);
+
+        assert!(width <= line * 1.5);
+        assert_eq!(height, 16.0 * 3.0 + (options.line_advance() - 16.0) * 2.0);
+        assert_eq!(system.measure_block("", 16.0, 100.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_line_metrics() {
+        let system = FontSystem::modern();
+        for variant in [
+            FontVariant::Regular,
+            FontVariant::Bold,
+            FontVariant::Italic,
+            FontVariant::BoldItalic,
+        ] {
+            let metrics = system.line_metrics(16.0, variant);
+            assert!(metrics.ascent > 0.0 && metrics.descent <= 0.0);
+            assert_eq!(system.ascent_ratio(variant), metrics.ascent / 16.0);
+
+            let vertical = system.vertical_metrics(16.0, variant);
+            assert_eq!(vertical.ascent, metrics.ascent);
+            assert!(0.0 < vertical.x_height && vertical.x_height < vertical.cap_height);
+            assert!(vertical.cap_height <= vertical.ascent);
+        }
+    }
+
+    #[test]
+    fn test_measure_kerning() {
+        let system = FontSystem::modern();
+        let a = system.measure_char('A', 16.0, FontVariant::Regular);
+        let v = system.measure_char('V', 16.0, FontVariant::Regular);
+        let kern = system.kerning('A', 'V', 16.0, FontVariant::Regular);
+        assert_eq!(system.measure_text("AV", 16.0), a + kern + v);
+        assert_eq!(
+            system.measure_text("A V", 16.0),
+            system.measure_text("A", 16.0) + system.measure_text(" V", 16.0)
+        );
+    }
+
+    #[test]
+    fn test_missing_glyphs() {
+        let system = FontSystem::modern();
+        assert!(system.has_glyph_in('A', FontFamily::Minecraft, FontVariant::Bold));
+        assert_eq!(
+            system.missing_glyphs(
+                "Hi \u{1F600}\u{1F600}!",
+                FontFamily::Minecraft,
+                FontVariant::Regular
+            ),
+            vec!['\u{1F600}']
+        );
+    }
+
+    #[test]
+    fn test_glyph_cache() {
+        let system = FontSystem::modern();
+        let first = system.rasterize('A', 16.0, FontVariant::Regular);
+        let second = system.rasterize('A', 16.2, FontVariant::Regular);
+        assert!(Arc::ptr_eq(&first.1, &second.1));
+        assert_eq!(system.glyph_cache_len(), 1);
+        system.rasterize('A', 16.5, FontVariant::Regular);
+        system.rasterize_family('A', 16.0, FontFamily::Minecraft);
+        assert_eq!(system.glyph_cache_len(), 3);
+
+        system.clear_glyph_cache();
+        assert_eq!(system.glyph_cache_len(), 0);
+    }
+
+    #[test]
+    fn test_fallback_font() {
+        let system = FontSystem::modern();
+        let primary = system.measure_char('A', 16.0, FontVariant::Regular);
+        assert!(system.has_glyph('A'));
+        assert!(!system.has_glyph('\u{1F600}'));
+
+        let system = system
+            .with_fallback_font(crate::fonts::MINECRAFT_BOLD.to_vec())
+            .unwrap();
+        assert!(!system.has_glyph('\u{1F600}'));
+        let measured = system.measure_char('A', 16.0, FontVariant::Regular);
+        assert!((measured - primary).abs() < 0.001);
+        assert!(FontSystem::modern().with_fallback_font(vec![0; 4]).is_err());
+    }
+
+    #[test]
+    fn test_custom_font() {
+        let system = FontSystem::modern();
+        assert!(matches!(
+            FontSystem::modern().with_custom_font("broken", vec![0, 1, 2, 3]),
+            Err(FontError::InvalidFont(_))
+        ));
+
+        let system = system
+            .with_custom_font("pack", crate::fonts::MINECRAFT_BOLD.to_vec())
+            .unwrap();
+        assert!(system.has_custom_font("pack"));
+        assert!(!system.has_custom_font("missing"));
+        let custom = system.measure_text_named("Hello", 16.0, "pack");
+        let bold = system.measure_text_styled("Hello", 16.0, FontVariant::Bold);
+        assert!((custom - bold).abs() < 0.001);
+    }
 }
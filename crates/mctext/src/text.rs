@@ -1,25 +1,93 @@
-use std::ops::{Add, AddAssign};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+use core::ops::{Add, AddAssign, Range};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
+#[cfg(feature = "bedrock-colors")]
+use crate::color::BedrockColor;
 use crate::color::{NamedColor, TextColor};
+use crate::event::{ClickEvent, HoverEvent};
+use crate::fonts::FontFamily;
+use crate::json::ParseError;
+use crate::minimessage;
 use crate::style::{Style, is_format_code, is_reset_code};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "unicode")]
+use unicode_segmentation::UnicodeSegmentation;
+#[cfg(feature = "unicode")]
+use unicode_width::UnicodeWidthStr;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Span {
     pub text: String,
     pub color: Option<TextColor>,
     pub style: Style,
+    pub click_event: Option<ClickEvent>,
+    pub hover_event: Option<HoverEvent>,
+    pub translate: Option<Translation>,
+    pub content: Option<ComponentContent>,
+    pub font: Option<String>,
+    pub insertion: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Translation {
+    pub key: String,
+    pub with: Vec<MCText>,
+    pub fallback: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ComponentContent {
+    Score {
+        name: String,
+        objective: String,
+        value: Option<String>,
+    },
+    Selector {
+        selector: String,
+        separator: Option<MCText>,
+    },
+    Keybind(String),
+}
+
+impl ComponentContent {
+    pub fn placeholder(&self) -> &str {
+        match self {
+            ComponentContent::Score { name, value, .. } => value.as_deref().unwrap_or(name),
+            ComponentContent::Selector { selector, .. } => selector,
+            ComponentContent::Keybind(key) => key,
+        }
+    }
+}
+
+impl Translation {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_args(mut self, args: Vec<MCText>) -> Self {
+        self.with = args;
+        self
+    }
 }
 
 impl Span {
     pub fn new(text: impl Into<String>) -> Self {
         Self {
             text: text.into(),
-            color: None,
-            style: Style::default(),
+            ..Default::default()
         }
     }
 
@@ -33,27 +101,308 @@ impl Span {
         self
     }
 
+    pub fn with_click_event(mut self, event: ClickEvent) -> Self {
+        self.click_event = Some(event);
+        self
+    }
+
+    pub fn with_hover_event(mut self, event: HoverEvent) -> Self {
+        self.hover_event = Some(event);
+        self
+    }
+
+    pub fn with_translation(mut self, translation: Translation) -> Self {
+        self.translate = Some(translation);
+        self
+    }
+
+    pub fn with_content(mut self, content: ComponentContent) -> Self {
+        self.content = Some(content);
+        self
+    }
+
+    pub fn with_font(mut self, font: impl Into<String>) -> Self {
+        self.font = Some(font.into());
+        self
+    }
+
+    pub fn with_insertion(mut self, insertion: impl Into<String>) -> Self {
+        self.insertion = Some(insertion.into());
+        self
+    }
+
+    pub fn font_family(&self) -> Option<FontFamily> {
+        self.font.as_deref().and_then(FontFamily::from_resource)
+    }
+
+    fn same_format(&self, other: &Span) -> bool {
+        self.color == other.color
+            && self.style == other.style
+            && self.click_event == other.click_event
+            && self.hover_event == other.hover_event
+            && self.translate == other.translate
+            && self.content == other.content
+            && self.font == other.font
+            && self.insertion == other.insertion
+    }
+
+    fn hash_format<H: Hasher>(&self, state: &mut H) {
+        self.color.hash(state);
+        self.style.hash(state);
+        self.click_event.hash(state);
+        self.hover_event.hash(state);
+        self.translate.hash(state);
+        self.content.hash(state);
+        self.font.hash(state);
+        self.insertion.hash(state);
+    }
+
+    pub(crate) fn is_atomic(&self) -> bool {
+        self.translate.is_some() || self.content.is_some()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.text.is_empty()
     }
+
+    pub fn is_plain(&self) -> bool {
+        self.color.is_none()
+            && self.style == Style::default()
+            && self.click_event.is_none()
+            && self.hover_event.is_none()
+            && self.font.is_none()
+            && self.insertion.is_none()
+            && !self.is_atomic()
+    }
+
+    fn inherit_from(mut self, parent: &Span) -> Span {
+        if self.color.is_none() {
+            self.color = parent.color;
+        }
+        self.style = self.style.merge(&parent.style);
+        if self.click_event.is_none() {
+            self.click_event = parent.click_event.clone();
+        }
+        if self.hover_event.is_none() {
+            self.hover_event = parent.hover_event.clone();
+        }
+        if self.font.is_none() {
+            self.font = parent.font.clone();
+        }
+        if self.insertion.is_none() {
+            self.insertion = parent.insertion.clone();
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownCode {
+    #[default]
+    Drop,
+    Keep,
+    Literal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub prefix: char,
+    pub color_resets_format: bool,
+    pub unknown_code: UnknownCode,
+    pub hex_colors: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            prefix: '\u{00A7}',
+            color_resets_format: true,
+            unknown_code: UnknownCode::Drop,
+            hex_colors: true,
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn with_prefix(mut self, prefix: char) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    pub fn with_color_resets_format(mut self, resets: bool) -> Self {
+        self.color_resets_format = resets;
+        self
+    }
+
+    pub fn with_unknown_code(mut self, policy: UnknownCode) -> Self {
+        self.unknown_code = policy;
+        self
+    }
+
+    pub fn with_hex_colors(mut self, hex: bool) -> Self {
+        self.hex_colors = hex;
+        self
+    }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MCText {
     spans: Vec<Span>,
 }
 
+impl PartialEq for MCText {
+    fn eq(&self, other: &Self) -> bool {
+        let mut left = self.normalized_runs();
+        let mut right = other.normalized_runs();
+        loop {
+            match (left.next(), right.next()) {
+                (None, None) => return true,
+                (Some(a), Some(b)) => {
+                    if !a[0].same_format(&b[0]) || !run_bytes(a).eq(run_bytes(b)) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl Eq for MCText {}
+
+impl Hash for MCText {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut count = 0usize;
+        for run in self.normalized_runs() {
+            run[0].hash_format(state);
+            run_bytes(run).for_each(|byte| state.write_u8(byte));
+            state.write_u8(0xFF);
+            count += 1;
+        }
+        state.write_usize(count);
+    }
+}
+
+fn run_bytes(run: &[Span]) -> impl Iterator<Item = u8> + '_ {
+    run.iter().flat_map(|span| span.text.bytes())
+}
+
+const RAINBOW_SATURATION: f32 = 1.0;
+const RAINBOW_VALUE: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy)]
+enum Fill {
+    Gradient(TextColor, TextColor),
+    Rainbow {
+        phase: f32,
+        saturation: f32,
+        value: f32,
+    },
+}
+
+impl Fill {
+    fn color_at(&self, index: usize, count: usize) -> TextColor {
+        match *self {
+            Fill::Gradient(start, end) => {
+                let t = if count > 1 {
+                    index as f32 / (count - 1) as f32
+                } else {
+                    0.0
+                };
+                TextColor::lerp(&start, &end, t)
+            }
+            Fill::Rainbow {
+                phase,
+                saturation,
+                value,
+            } => {
+                let position = index as f32 / count.max(1) as f32;
+                TextColor::from_hsv((position + phase) * 360.0, saturation, value)
+            }
+        }
+    }
+}
+
 pub struct SpanBuilder {
     mctext: MCText,
     text: String,
     color: Option<TextColor>,
+    fill: Option<Fill>,
     style: Style,
+    placeholder: bool,
 }
 
 impl SpanBuilder {
+    fn new(mctext: MCText, text: String) -> Self {
+        Self {
+            mctext,
+            text,
+            color: None,
+            fill: None,
+            style: Style::default(),
+            placeholder: false,
+        }
+    }
+
     pub fn color(mut self, color: impl Into<TextColor>) -> Self {
         self.color = Some(color.into());
+        self.fill = None;
+        self
+    }
+
+    pub fn hex(self, hex: &str) -> Self {
+        match TextColor::from_hex(hex) {
+            Ok(color) => self.color(color),
+            Err(_) => self,
+        }
+    }
+
+    pub fn try_hex(self, hex: &str) -> Result<Self, ParseError> {
+        TextColor::from_hex(hex).map(|color| self.color(color))
+    }
+
+    pub fn rgb(self, r: u8, g: u8, b: u8) -> Self {
+        self.color(TextColor::Rgb { r, g, b })
+    }
+
+    pub fn gradient(mut self, start: impl Into<TextColor>, end: impl Into<TextColor>) -> Self {
+        self.fill = Some(Fill::Gradient(start.into(), end.into()));
+        self.color = None;
+        self
+    }
+
+    pub fn rainbow(self) -> Self {
+        self.rainbow_from(0.0)
+    }
+
+    pub fn rainbow_from(mut self, phase: f32) -> Self {
+        let (saturation, value) = match self.fill {
+            Some(Fill::Rainbow {
+                saturation, value, ..
+            }) => (saturation, value),
+            _ => (RAINBOW_SATURATION, RAINBOW_VALUE),
+        };
+        self.fill = Some(Fill::Rainbow {
+            phase,
+            saturation,
+            value,
+        });
+        self.color = None;
+        self
+    }
+
+    pub fn rainbow_with(mut self, saturation: f32, value: f32) -> Self {
+        let phase = match self.fill {
+            Some(Fill::Rainbow { phase, .. }) => phase,
+            _ => 0.0,
+        };
+        self.fill = Some(Fill::Rainbow {
+            phase,
+            saturation: saturation.clamp(0.0, 1.0),
+            value: value.clamp(0.0, 1.0),
+        });
+        self.color = None;
         self
     }
 
@@ -82,28 +431,58 @@ impl SpanBuilder {
         self
     }
 
+    pub fn reset(mut self) -> Self {
+        self.color = None;
+        self.fill = None;
+        self.style = Style::default();
+        self
+    }
+
     pub fn then(mut self, text: impl Into<String>) -> SpanBuilder {
-        self.mctext.spans.push(Span {
-            text: self.text,
-            color: self.color,
-            style: self.style,
-        });
+        self.flush();
+        SpanBuilder::new(self.mctext, text.into())
+    }
+
+    pub fn append(mut self, other: MCText) -> SpanBuilder {
+        self.flush();
+        self.mctext.append(other);
         SpanBuilder {
-            mctext: self.mctext,
-            text: text.into(),
-            color: None,
-            style: Style::default(),
+            placeholder: true,
+            ..SpanBuilder::new(self.mctext, String::new())
         }
     }
 
     pub fn build(mut self) -> MCText {
-        self.mctext.spans.push(Span {
-            text: self.text,
-            color: self.color,
-            style: self.style,
-        });
+        self.flush();
         self.mctext
     }
+
+    fn flush(&mut self) {
+        let text = core::mem::take(&mut self.text);
+        if self.placeholder && text.is_empty() {
+            return;
+        }
+
+        let Some(fill) = self.fill else {
+            self.mctext.spans.push(Span {
+                text,
+                color: self.color,
+                style: self.style,
+                ..Default::default()
+            });
+            return;
+        };
+
+        let count = text.chars().count();
+        for (i, ch) in text.chars().enumerate() {
+            self.mctext.spans.push(Span {
+                text: ch.to_string(),
+                color: Some(fill.color_at(i, count)),
+                style: self.style,
+                ..Default::default()
+            });
+        }
+    }
 }
 
 impl MCText {
@@ -112,51 +491,46 @@ impl MCText {
     }
 
     pub fn parse(text: &str) -> Self {
-        let mut spans = Vec::new();
-        let mut current_text = String::new();
-        let mut current_color: Option<TextColor> = None;
-        let mut current_style = Style::default();
-        let mut chars = text.chars().peekable();
-
-        while let Some(ch) = chars.next() {
-            if ch == '\u{00A7}' {
-                if let Some(&code) = chars.peek() {
-                    if !current_text.is_empty() {
-                        spans.push(Span {
-                            text: std::mem::take(&mut current_text),
-                            color: current_color,
-                            style: current_style,
-                        });
-                    }
+        Self::parse_with_prefix(text, '\u{00A7}')
+    }
 
-                    chars.next();
-
-                    if is_reset_code(code) {
-                        current_color = None;
-                        current_style = Style::default();
-                    } else if let Some(named) = NamedColor::from_code(code) {
-                        current_color = Some(TextColor::Named(named));
-                        current_style = Style::default();
-                    } else if is_format_code(code) {
-                        if let Some(style) = Style::from_code(code) {
-                            current_style = current_style.merge(&style);
-                        }
-                    }
-                } else {
-                    current_text.push(ch);
-                }
-            } else {
-                current_text.push(ch);
-            }
-        }
+    pub fn parse_minimessage(input: &str) -> Result<Self, ParseError> {
+        minimessage::parse(input)
+    }
 
-        if !current_text.is_empty() {
-            spans.push(Span {
-                text: current_text,
-                color: current_color,
-                style: current_style,
-            });
-        }
+    pub fn to_minimessage(&self) -> String {
+        minimessage::serialize(self)
+    }
+
+    pub fn parse_lenient(text: &str) -> Self {
+        Self::parse(&repair_section_signs(text))
+    }
+
+    pub fn parse_amp(text: &str) -> Self {
+        Self::parse_with_prefix(text, '&')
+    }
+
+    pub fn parse_with_prefix(text: &str, prefix: char) -> Self {
+        let unknown_code = if prefix == '\u{00A7}' {
+            UnknownCode::Drop
+        } else {
+            UnknownCode::Keep
+        };
+        let options = ParseOptions::default()
+            .with_prefix(prefix)
+            .with_unknown_code(unknown_code);
+        Self::parse_with_options(text, &options)
+    }
+
+    pub fn parse_with_options(text: &str, options: &ParseOptions) -> Self {
+        let spans = Segments::new(text, *options)
+            .map(|segment| Span {
+                text: segment.text.to_string(),
+                color: segment.color,
+                style: segment.style,
+                ..Default::default()
+            })
+            .collect();
 
         Self { spans }
     }
@@ -165,6 +539,10 @@ impl MCText {
         &self.spans
     }
 
+    pub fn from_spans(spans: Vec<Span>) -> Self {
+        Self { spans }
+    }
+
     pub fn into_spans(self) -> Vec<Span> {
         self.spans
     }
@@ -174,18 +552,23 @@ impl MCText {
     }
 
     pub fn span(self, text: impl Into<String>) -> SpanBuilder {
-        SpanBuilder {
-            mctext: self,
-            text: text.into(),
-            color: None,
-            style: Style::default(),
-        }
+        SpanBuilder::new(self, text.into())
     }
 
     pub fn is_empty(&self) -> bool {
         self.spans.is_empty() || self.spans.iter().all(|s| s.is_empty())
     }
 
+    pub fn is_plain(&self) -> bool {
+        self.spans.iter().all(Span::is_plain)
+    }
+
+    pub fn has_rgb(&self) -> bool {
+        self.spans
+            .iter()
+            .any(|s| matches!(s.color, Some(TextColor::Rgb { .. })))
+    }
+
     pub fn append(&mut self, other: MCText) {
         self.spans.extend(other.spans);
     }
@@ -195,105 +578,908 @@ impl MCText {
         self
     }
 
-    pub fn plain_text(&self) -> String {
-        self.spans.iter().map(|s| s.text.as_str()).collect()
-    }
-
-    pub fn to_legacy(&self) -> String {
-        let mut result = String::new();
+    #[cfg(feature = "std")]
+    pub fn resolve_translations(&self, table: &HashMap<String, String>) -> MCText {
+        let mut resolved = MCText::new();
 
         for span in &self.spans {
-            if let Some(TextColor::Named(color)) = span.color {
-                result.push('\u{00A7}');
-                result.push(color.code());
-            }
-            if span.style.bold {
-                result.push_str("\u{00A7}l");
-            }
-            if span.style.italic {
-                result.push_str("\u{00A7}o");
-            }
-            if span.style.underlined {
-                result.push_str("\u{00A7}n");
-            }
-            if span.style.strikethrough {
-                result.push_str("\u{00A7}m");
-            }
-            if span.style.obfuscated {
-                result.push_str("\u{00A7}k");
+            let Some(translation) = &span.translate else {
+                resolved.push(span.clone());
+                continue;
+            };
+
+            let template = table
+                .get(&translation.key)
+                .or(translation.fallback.as_ref())
+                .unwrap_or(&translation.key);
+            let base = Span {
+                translate: None,
+                ..span.clone()
+            };
+
+            for piece in format_translation(template, &translation.with, &base, table) {
+                if !piece.is_empty() {
+                    resolved.push(piece);
+                }
             }
-            result.push_str(&span.text);
         }
 
-        result
+        resolved
     }
-}
 
-pub fn strip_codes(text: &str) -> String {
-    let mut result = String::with_capacity(text.len());
-    let mut chars = text.chars().peekable();
+    #[cfg(feature = "std")]
+    pub fn substitute_tokens(&self, table: &HashMap<String, char>) -> MCText {
+        let spans = self
+            .spans
+            .iter()
+            .map(|span| {
+                if span.is_atomic() || !span.text.contains(':') {
+                    return span.clone();
+                }
+                Span {
+                    text: substitute_span_tokens(&span.text, table),
+                    ..span.clone()
+                }
+            })
+            .collect();
+        MCText { spans }
+    }
 
-    while let Some(ch) = chars.next() {
-        if ch == '\u{00A7}' {
-            chars.next();
-        } else {
-            result.push(ch);
+    pub fn map_spans(self, f: impl FnMut(Span) -> Span) -> MCText {
+        MCText {
+            spans: self.spans.into_iter().map(f).collect(),
         }
     }
 
-    result
-}
+    pub fn with_color_override(self, color: impl Into<TextColor>) -> MCText {
+        let color = color.into();
+        self.map_spans(|span| Span {
+            color: Some(color),
+            ..span
+        })
+    }
 
-pub fn count_visible_chars(text: &str) -> usize {
-    let mut count = 0;
-    let mut chars = text.chars().peekable();
+    pub fn strip_colors(self) -> MCText {
+        self.map_spans(|span| Span {
+            color: None,
+            ..span
+        })
+    }
 
-    while let Some(ch) = chars.next() {
-        if ch == '\u{00A7}' {
-            chars.next();
-        } else {
-            count += 1;
-        }
+    pub fn strip_styles(self) -> MCText {
+        self.map_spans(|span| Span {
+            style: Style::default(),
+            ..span
+        })
     }
 
-    count
-}
+    pub fn scramble_obfuscated(&self, seed: u64) -> MCText {
+        let pool: Vec<char> = OBFUSCATION_POOL.chars().collect();
+        let mut state = seed;
 
-impl<'a> IntoIterator for &'a MCText {
-    type Item = &'a Span;
-    type IntoIter = std::slice::Iter<'a, Span>;
+        let spans = self
+            .spans
+            .iter()
+            .map(|span| {
+                if !span.style.obfuscated {
+                    return span.clone();
+                }
+                let text = span
+                    .text
+                    .chars()
+                    .map(|ch| {
+                        if ch.is_whitespace() || ch.is_control() {
+                            ch
+                        } else {
+                            pool[(next_random(&mut state) % pool.len() as u64) as usize]
+                        }
+                    })
+                    .collect();
+                Span {
+                    text,
+                    ..span.clone()
+                }
+            })
+            .collect();
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.spans.iter()
+        MCText { spans }
     }
-}
 
-impl Add for MCText {
-    type Output = MCText;
+    pub fn normalized(&self) -> MCText {
+        let spans = self
+            .normalized_runs()
+            .map(|run| Span {
+                text: run.iter().map(|span| span.text.as_str()).collect(),
+                ..run[0].clone()
+            })
+            .collect();
+        MCText { spans }
+    }
 
-    fn add(self, other: MCText) -> MCText {
-        self.concat(other)
+    fn normalized_runs(&self) -> impl Iterator<Item = &[Span]> + '_ {
+        let skipped = |span: &Span| span.text.is_empty() && !span.is_atomic();
+        let mut rest = self.spans.as_slice();
+        core::iter::from_fn(move || {
+            let start = rest.iter().position(|span| !skipped(span))?;
+            let head = &rest[start];
+            let len = rest[start + 1..]
+                .iter()
+                .position(|span| {
+                    !skipped(span)
+                        && (head.is_atomic() || span.is_atomic() || !head.same_format(span))
+                })
+                .map_or(rest.len() - start, |end| end + 1);
+            let run = &rest[start..start + len];
+            rest = &rest[start + len..];
+            Some(run)
+        })
     }
-}
 
-impl AddAssign for MCText {
-    fn add_assign(&mut self, other: MCText) {
-        self.append(other);
+    pub fn char_count(&self) -> usize {
+        self.spans.iter().map(|s| s.text.chars().count()).sum()
     }
-}
 
-impl IntoIterator for MCText {
-    type Item = Span;
-    type IntoIter = std::vec::IntoIter<Span>;
+    pub fn byte_len(&self) -> usize {
+        self.spans.iter().map(|s| s.text.len()).sum()
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.spans.into_iter()
+    pub fn utf16_len(&self) -> usize {
+        self.spans
+            .iter()
+            .flat_map(|s| s.text.chars())
+            .map(char::len_utf16)
+            .sum()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[cfg(feature = "unicode")]
+    pub fn grapheme_count(&self) -> usize {
+        self.plain_text().graphemes(true).count()
+    }
+
+    #[cfg(feature = "unicode")]
+    pub fn terminal_width(&self) -> usize {
+        self.spans.iter().map(|s| s.text.width()).sum()
+    }
+
+    pub fn styled_chars(&self) -> impl Iterator<Item = (char, Option<TextColor>, Style)> + '_ {
+        self.spans
+            .iter()
+            .flat_map(|span| span.text.chars().map(|ch| (ch, span.color, span.style)))
+    }
+
+    pub fn char_to_span_pos(&self, index: usize) -> Option<(usize, usize)> {
+        let mut remaining = index;
+        for (i, span) in self.spans.iter().enumerate() {
+            if let Some((offset, _)) = span.text.char_indices().nth(remaining) {
+                return Some((i, offset));
+            }
+            remaining -= span.text.chars().count();
+        }
+        match self.spans.len() {
+            0 => None,
+            len if remaining == 0 => Some((len - 1, self.spans[len - 1].text.len())),
+            _ => None,
+        }
+    }
+
+    pub fn span_pos_to_char(&self, span: usize, offset: usize) -> usize {
+        let before: usize = self
+            .spans
+            .iter()
+            .take(span)
+            .map(|s| s.text.chars().count())
+            .sum();
+        let Some(current) = self.spans.get(span) else {
+            return before;
+        };
+        before
+            + current
+                .text
+                .char_indices()
+                .take_while(|(i, _)| *i < offset)
+                .count()
+    }
+
+    pub fn slice(&self, range: Range<usize>) -> MCText {
+        let mut sliced = MCText::new();
+        let mut offset = 0;
+
+        for span in &self.spans {
+            let len = span.text.chars().count();
+            let start = range.start.clamp(offset, offset + len) - offset;
+            let end = range.end.clamp(offset, offset + len) - offset;
+            let covered = range.start <= offset && offset + len <= range.end && offset < range.end;
+            offset += len;
+
+            if span.is_atomic() {
+                if covered {
+                    sliced.push(span.clone());
+                }
+            } else if start < end {
+                sliced.push(Span {
+                    text: span.text.chars().skip(start).take(end - start).collect(),
+                    ..span.clone()
+                });
+            }
+        }
+
+        sliced
+    }
+
+    pub fn lines(&self) -> Vec<MCText> {
+        let mut lines = Vec::new();
+        let mut current = MCText::new();
+
+        for span in &self.spans {
+            if span.is_atomic() {
+                current.push(span.clone());
+                continue;
+            }
+
+            let mut parts = span.text.split('\n');
+            if let Some(first) = parts.next() {
+                if !first.is_empty() {
+                    current.push(Span {
+                        text: first.into(),
+                        ..span.clone()
+                    });
+                }
+            }
+            for part in parts {
+                lines.push(core::mem::take(&mut current));
+                if !part.is_empty() {
+                    current.push(Span {
+                        text: part.into(),
+                        ..span.clone()
+                    });
+                }
+            }
+        }
+
+        let ends_with_newline = self
+            .spans
+            .iter()
+            .rev()
+            .find(|span| !span.text.is_empty())
+            .is_some_and(|span| !span.is_atomic() && span.text.ends_with('\n'));
+        if !ends_with_newline || lines.is_empty() {
+            lines.push(current);
+        }
+
+        lines
+    }
+
+    pub fn trim(&self) -> MCText {
+        self.trim_start().trim_end()
+    }
+
+    pub fn trim_start(&self) -> MCText {
+        let mut spans = self.spans.iter();
+        let mut trimmed = MCText::new();
+
+        for span in spans.by_ref() {
+            if span.is_atomic() {
+                trimmed.push(span.clone());
+                break;
+            }
+            let text = span.text.trim_start();
+            if !text.is_empty() {
+                trimmed.push(Span {
+                    text: text.into(),
+                    ..span.clone()
+                });
+                break;
+            }
+        }
+
+        trimmed.spans.extend(spans.cloned());
+        trimmed
+    }
+
+    pub fn trim_end(&self) -> MCText {
+        let mut spans = self.spans.iter().rev();
+        let mut trimmed = Vec::new();
+
+        for span in spans.by_ref() {
+            if span.is_atomic() {
+                trimmed.push(span.clone());
+                break;
+            }
+            let text = span.text.trim_end();
+            if !text.is_empty() {
+                trimmed.push(Span {
+                    text: text.into(),
+                    ..span.clone()
+                });
+                break;
+            }
+        }
+
+        trimmed.extend(spans.cloned());
+        trimmed.reverse();
+        MCText { spans: trimmed }
+    }
+
+    pub fn replace(&self, from: &str, to: &str) -> MCText {
+        self.replace_with(from, |_| MCText::new().span(to).build())
+    }
+
+    pub fn replace_with(&self, from: &str, mut replacement: impl FnMut(&str) -> MCText) -> MCText {
+        if from.is_empty() {
+            return self.clone();
+        }
+
+        let mut result = MCText::new();
+        let mut run = MCText::new();
+
+        for span in &self.spans {
+            if span.is_atomic() {
+                result.append(core::mem::take(&mut run).replace_run(from, &mut replacement));
+                result.push(span.clone());
+            } else {
+                run.push(span.clone());
+            }
+        }
+
+        result.append(run.replace_run(from, &mut replacement));
+        result
+    }
+
+    fn replace_run(&self, from: &str, replacement: &mut impl FnMut(&str) -> MCText) -> MCText {
+        let plain = self.plain_text();
+        let mut result = MCText::new();
+        let mut cursor = 0;
+        let mut byte_cursor = 0;
+
+        for (byte_start, matched) in plain.match_indices(from) {
+            let start = cursor + plain[byte_cursor..byte_start].chars().count();
+            result.append(self.slice(cursor..start));
+
+            let mut offset = 0;
+            let base = self.spans.iter().find(|span| {
+                offset += span.text.chars().count();
+                offset > start
+            });
+            for span in replacement(matched).spans {
+                result.push(match base {
+                    Some(base) => span.inherit_from(base),
+                    None => span,
+                });
+            }
+
+            byte_cursor = byte_start + matched.len();
+            cursor = start + matched.chars().count();
+        }
+
+        result.append(self.slice(cursor..usize::MAX));
+        result
+    }
+
+    pub fn plain_text(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    pub fn to_legacy(&self) -> String {
+        self.to_legacy_with(&ParseOptions::default())
+    }
+
+    pub fn to_legacy_with(&self, options: &ParseOptions) -> String {
+        let prefix = options.prefix;
+        let mut result = String::new();
+        let mut previous: (Option<TextColor>, Style) = (None, Style::default());
+
+        for span in &self.spans {
+            let (previous_color, previous_style) = previous;
+            let removes_flags = previous_style.merge(&span.style) != span.style;
+            let needs_reset = match span.color {
+                None => previous_color.is_some() || previous_style != Style::default(),
+                Some(_) => !options.color_resets_format && removes_flags,
+            };
+            if needs_reset {
+                result.push(prefix);
+                result.push('r');
+            }
+            previous = (span.color, span.style);
+
+            match span.color {
+                Some(TextColor::Named(color)) => {
+                    result.push(prefix);
+                    result.push(color.code());
+                }
+                Some(color @ TextColor::Rgb { .. }) if !options.hex_colors => {
+                    result.push(prefix);
+                    result.push(color.nearest_named().code());
+                }
+                Some(TextColor::Rgb { r, g, b }) => {
+                    result.push(prefix);
+                    result.push('x');
+                    for digit in format!("{:02x}{:02x}{:02x}", r, g, b).chars() {
+                        result.push(prefix);
+                        result.push(digit);
+                    }
+                }
+                None => {}
+            }
+            let flags = [
+                (span.style.bold, 'l'),
+                (span.style.italic, 'o'),
+                (span.style.underlined, 'n'),
+                (span.style.strikethrough, 'm'),
+                (span.style.obfuscated, 'k'),
+            ];
+            for (_, code) in flags.iter().filter(|(enabled, _)| *enabled) {
+                result.push(prefix);
+                result.push(*code);
+            }
+            result.push_str(&span.text);
+        }
+
+        result
+    }
+
+    pub fn to_ansi(&self) -> String {
+        self.to_ansi_with(|(r, g, b)| format!("38;2;{};{};{}", r, g, b))
+    }
+
+    pub fn to_ansi_256(&self) -> String {
+        self.to_ansi_with(|(r, g, b)| format!("38;5;{}", ansi_256_index(r, g, b)))
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut result = String::new();
+
+        for span in &self.spans {
+            let mut styles = Vec::new();
+            if let Some(color) = span.color {
+                let (r, g, b) = color.rgb();
+                styles.push(format!("color:#{:02x}{:02x}{:02x}", r, g, b));
+            }
+            if span.style.bold {
+                styles.push("font-weight:bold".to_string());
+            }
+            if span.style.italic {
+                styles.push("font-style:italic".to_string());
+            }
+            let decorations: Vec<&str> = [
+                (span.style.underlined, "underline"),
+                (span.style.strikethrough, "line-through"),
+            ]
+            .into_iter()
+            .filter_map(|(enabled, name)| enabled.then_some(name))
+            .collect();
+            if !decorations.is_empty() {
+                styles.push(format!("text-decoration:{}", decorations.join(" ")));
+            }
+
+            let mut text = String::new();
+            for ch in span.text.chars() {
+                match ch {
+                    '&' => text.push_str("&amp;"),
+                    '<' => text.push_str("&lt;"),
+                    '>' => text.push_str("&gt;"),
+                    '"' => text.push_str("&quot;"),
+                    '\n' => text.push_str("<br>"),
+                    _ => text.push(ch),
+                }
+            }
+
+            if styles.is_empty() && !span.style.obfuscated {
+                result.push_str(&text);
+                continue;
+            }
+
+            result.push_str("<span");
+            if span.style.obfuscated {
+                result.push_str(" class=\"mc-obfuscated\"");
+            }
+            if !styles.is_empty() {
+                result.push_str(&format!(" style=\"{}\"", styles.join("; ")));
+            }
+            result.push('>');
+            result.push_str(&text);
+            result.push_str("</span>");
+        }
+
+        result
+    }
+
+    fn to_ansi_with(&self, color_code: impl Fn((u8, u8, u8)) -> String) -> String {
+        let mut result = String::new();
+
+        for span in &self.spans {
+            let mut codes = Vec::new();
+            if let Some(color) = span.color {
+                codes.push(color_code(color.rgb()));
+            }
+            if span.style.bold {
+                codes.push("1".to_string());
+            }
+            if span.style.italic {
+                codes.push("3".to_string());
+            }
+            if span.style.underlined {
+                codes.push("4".to_string());
+            }
+            if span.style.strikethrough {
+                codes.push("9".to_string());
+            }
+            if span.style.obfuscated {
+                codes.push("5".to_string());
+            }
+
+            for code in &codes {
+                result.push_str(&format!("\x1b[{}m", code));
+            }
+            result.push_str(&span.text);
+            if !codes.is_empty() {
+                result.push_str("\x1b[0m");
+            }
+        }
+
+        result
+    }
+}
+
+pub(crate) const OBFUSCATION_POOL: &str = "!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+
+pub(crate) fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(feature = "std")]
+fn substitute_span_tokens(text: &str, table: &HashMap<String, char>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(':') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let token = after
+            .find(':')
+            .and_then(|end| table.get(&after[..end]).map(|&ch| (end, ch)));
+        match token {
+            Some((end, ch)) => {
+                result.push(ch);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push(':');
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(feature = "std")]
+fn format_translation(
+    template: &str,
+    args: &[MCText],
+    base: &Span,
+    table: &HashMap<String, String>,
+) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut literal = String::new();
+    let mut next_arg = 0;
+    let mut chars = template.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            literal.push(ch);
+            continue;
+        }
+
+        let mut lookahead = chars.clone();
+        let mut digits = String::new();
+        while let Some(&d) = lookahead.peek().filter(|d| d.is_ascii_digit()) {
+            digits.push(d);
+            lookahead.next();
+        }
+
+        let index = match (digits.is_empty(), lookahead.next(), lookahead.peek()) {
+            (true, Some('%'), _) => {
+                chars = lookahead;
+                literal.push('%');
+                continue;
+            }
+            (true, Some('s'), _) => {
+                next_arg += 1;
+                next_arg - 1
+            }
+            (false, Some('$'), Some('s')) => {
+                lookahead.next();
+                match digits.parse::<usize>() {
+                    Ok(n) if n > 0 => n - 1,
+                    _ => {
+                        literal.push(ch);
+                        continue;
+                    }
+                }
+            }
+            _ => {
+                literal.push(ch);
+                continue;
+            }
+        };
+        chars = lookahead;
+
+        if !literal.is_empty() {
+            spans.push(Span {
+                text: core::mem::take(&mut literal),
+                ..base.clone()
+            });
+        }
+        if let Some(arg) = args.get(index) {
+            for span in arg.resolve_translations(table).into_spans() {
+                spans.push(span.inherit_from(base));
+            }
+        }
+    }
+
+    if !literal.is_empty() {
+        spans.push(Span {
+            text: literal,
+            ..base.clone()
+        });
+    }
+
+    spans
+}
+
+fn ansi_256_index(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let nearest_level = |v: u8| -> usize {
+        (0..LEVELS.len())
+            .min_by_key(|&i| (LEVELS[i] as i32 - v as i32).abs())
+            .unwrap_or(0)
+    };
+    let distance = |(r2, g2, b2): (u8, u8, u8)| -> i32 {
+        let dr = r as i32 - r2 as i32;
+        let dg = g as i32 - g2 as i32;
+        let db = b as i32 - b2 as i32;
+        dr * dr + dg * dg + db * db
+    };
+
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+    let cube_distance = distance((LEVELS[ri], LEVELS[gi], LEVELS[bi]));
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + gray_step * 10;
+    let gray_distance = distance((gray_level, gray_level, gray_level));
+
+    if gray_distance < cube_distance {
+        232 + gray_step
+    } else {
+        cube_index as u8
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<'a> {
+    pub text: &'a str,
+    pub color: Option<TextColor>,
+    pub style: Style,
+}
+
+struct Segments<'a> {
+    text: &'a str,
+    options: ParseOptions,
+    position: usize,
+    scan: usize,
+    color: Option<TextColor>,
+    style: Style,
+}
+
+impl<'a> Segments<'a> {
+    fn new(text: &'a str, options: ParseOptions) -> Self {
+        Self {
+            text,
+            options,
+            position: 0,
+            scan: 0,
+            color: None,
+            style: Style::default(),
+        }
+    }
+
+    fn segment(&self, start: usize, end: usize) -> Segment<'a> {
+        Segment {
+            text: &self.text[start..end],
+            color: self.color,
+            style: self.style,
+        }
+    }
+
+    fn finish(&mut self, start: usize) -> Option<Segment<'a>> {
+        self.position = self.text.len();
+        Some(self.segment(start, self.text.len()))
+    }
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Segment<'a>> {
+        let prefix = self.options.prefix;
+
+        while self.position < self.text.len() {
+            let start = self.position;
+            let Some(found) = self.text[self.scan..].find(prefix) else {
+                return self.finish(start);
+            };
+            let at = self.scan + found;
+            let after = at + prefix.len_utf8();
+            let Some(code) = self.text[after..].chars().next() else {
+                return self.finish(start);
+            };
+            let code_end = after + code.len_utf8();
+
+            let hex = if code.eq_ignore_ascii_case(&'x') {
+                parse_hex_sequence(&self.text[code_end..], prefix)
+            } else {
+                None
+            };
+            let named = color_from_code(code);
+            let known = hex.is_some() || named.is_some() || is_format_code(code);
+
+            if !known && self.options.unknown_code == UnknownCode::Keep {
+                self.scan = after;
+                continue;
+            }
+
+            let pending = self.segment(start, at);
+            self.position = code_end;
+            self.scan = code_end;
+
+            if let Some((color, len)) = hex {
+                self.position += len;
+                self.scan += len;
+                self.color = Some(color);
+                if self.options.color_resets_format {
+                    self.style = Style::default();
+                }
+            } else if let Some(named) = named {
+                self.color = Some(named);
+                if self.options.color_resets_format {
+                    self.style = Style::default();
+                }
+            } else if is_reset_code(code) {
+                self.color = None;
+                self.style = Style::default();
+            } else if let Some(style) = Style::from_code(code) {
+                self.style = self.style.merge(&style);
+            } else if self.options.unknown_code == UnknownCode::Literal {
+                self.position = after;
+            }
+
+            if !pending.text.is_empty() {
+                return Some(pending);
+            }
+        }
+
+        None
+    }
+}
+
+pub fn parse_iter(text: &str) -> impl Iterator<Item = Segment<'_>> {
+    Segments::new(text, ParseOptions::default())
+}
+
+fn color_from_code(code: char) -> Option<TextColor> {
+    let named = NamedColor::from_code(code).map(TextColor::Named);
+    #[cfg(feature = "bedrock-colors")]
+    let named = named.or_else(|| BedrockColor::from_code(code).map(TextColor::from));
+    named
+}
+
+fn parse_hex_sequence(rest: &str, prefix: char) -> Option<(TextColor, usize)> {
+    let mut chars = rest.chars();
+    let mut value = 0u32;
+
+    for _ in 0..6 {
+        if chars.next() != Some(prefix) {
+            return None;
+        }
+        value = value * 16 + chars.next()?.to_digit(16)?;
+    }
+
+    let [_, r, g, b] = value.to_be_bytes();
+    Some((
+        TextColor::Rgb { r, g, b },
+        rest.len() - chars.as_str().len(),
+    ))
+}
+
+const SECTION_SIGN_ENCODINGS: [&str; 6] = [
+    "\u{00C3}\u{201A}\u{00C2}\u{00A7}",
+    "\u{00C2}\u{00A7}",
+    "\\u00a7",
+    "\\u00A7",
+    "&#167;",
+    "&sect;",
+];
+
+pub fn repair_section_signs(text: &str) -> String {
+    SECTION_SIGN_ENCODINGS
+        .iter()
+        .fold(text.to_string(), |text, encoding| {
+            text.replace(encoding, "\u{00A7}")
+        })
+}
+
+pub fn strip_codes(text: &str) -> String {
+    parse_iter(text).map(|segment| segment.text).collect()
+}
+
+pub fn count_visible_chars(text: &str) -> usize {
+    parse_iter(text)
+        .map(|segment| segment.text.chars().count())
+        .sum()
+}
+
+#[cfg(feature = "unicode")]
+pub fn count_visible_graphemes(text: &str) -> usize {
+    strip_codes(text).graphemes(true).count()
+}
+
+impl<'a> IntoIterator for &'a MCText {
+    type Item = &'a Span;
+    type IntoIter = core::slice::Iter<'a, Span>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.iter()
+    }
+}
+
+impl Add for MCText {
+    type Output = MCText;
+
+    fn add(self, other: MCText) -> MCText {
+        self.concat(other)
+    }
+}
+
+impl AddAssign for MCText {
+    fn add_assign(&mut self, other: MCText) {
+        self.append(other);
+    }
+}
+
+impl Extend<Span> for MCText {
+    fn extend<I: IntoIterator<Item = Span>>(&mut self, iter: I) {
+        self.spans.extend(iter);
+    }
+}
+
+impl FromIterator<Span> for MCText {
+    fn from_iter<I: IntoIterator<Item = Span>>(iter: I) -> Self {
+        Self {
+            spans: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for MCText {
+    type Item = Span;
+    type IntoIter = alloc::vec::IntoIter<Span>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn test_parse() {
@@ -302,6 +1488,278 @@ mod tests {
         assert_eq!(text.plain_text(), "Hello World");
     }
 
+    #[test]
+    fn test_builder_reset() {
+        let text = MCText::new()
+            .span("a")
+            .underlined()
+            .strikethrough()
+            .then("b")
+            .obfuscated()
+            .color(NamedColor::Red)
+            .reset()
+            .build();
+        let spans = text.spans();
+        assert!(spans[0].style.underlined && spans[0].style.strikethrough);
+        assert_eq!(spans[1].style, Style::default());
+        assert_eq!(spans[1].color, None);
+    }
+
+    #[test]
+    fn test_builder_hex() {
+        let text = MCText::new()
+            .span("a")
+            .hex("#102030")
+            .then("b")
+            .rgb(1, 2, 3)
+            .then("c")
+            .color(NamedColor::Red)
+            .hex("nope")
+            .build();
+        let colors: Vec<_> = text.spans().iter().map(|s| s.color.unwrap()).collect();
+        assert_eq!(
+            colors[0],
+            TextColor::Rgb {
+                r: 16,
+                g: 32,
+                b: 48
+            }
+        );
+        assert_eq!(colors[1], TextColor::Rgb { r: 1, g: 2, b: 3 });
+        assert_eq!(colors[2], TextColor::Named(NamedColor::Red));
+
+        assert!(MCText::new().span("a").try_hex("nope").is_err());
+        let text = MCText::new().span("a").try_hex("#102030").unwrap().build();
+        assert_eq!(text.spans()[0].color, Some(colors[0]));
+    }
+
+    #[test]
+    fn test_parse_hex_sequence() {
+        let text = MCText::parse("§x§f§f§0§0§0§0Red§cNamed");
+        assert_eq!(text.spans().len(), 2);
+        assert_eq!(
+            text.spans()[0].color,
+            Some(TextColor::Rgb { r: 255, g: 0, b: 0 })
+        );
+        assert_eq!(text.spans()[0].text, "Red");
+        assert_eq!(
+            text.spans()[1].color,
+            Some(TextColor::Named(NamedColor::Red))
+        );
+        assert_eq!(text.to_legacy(), "§x§f§f§0§0§0§0Red§cNamed");
+    }
+
+    #[test]
+    fn test_parse_amp() {
+        let text = MCText::parse_amp("&cHello &lWorld &z& done");
+        assert_eq!(text.spans().len(), 2);
+        assert_eq!(
+            text.spans()[0].color,
+            Some(TextColor::Named(NamedColor::Red))
+        );
+        assert!(text.spans()[1].style.bold);
+        assert_eq!(text.plain_text(), "Hello World &z& done");
+    }
+
+    #[test]
+    fn test_strip_hex_codes() {
+        let text = "§x§f§f§0§0§0§0A§cB§x§1§2§3§4§5§6§lC§xD";
+        assert_eq!(strip_codes(text), "ABCD");
+        assert_eq!(count_visible_chars(text), 4);
+    }
+
+    #[test]
+    fn test_parse_iter() {
+        let text = "§cRed §lBold§r plain";
+        let segments: Vec<_> = parse_iter(text).collect();
+        assert_eq!(
+            segments.iter().map(|s| s.text).collect::<Vec<_>>(),
+            vec!["Red ", "Bold", " plain"]
+        );
+        assert_eq!(segments[1].color, Some(TextColor::Named(NamedColor::Red)));
+        assert!(segments[1].style.bold);
+        assert_eq!(segments[2].color, None);
+        let source = text.as_bytes().as_ptr_range();
+        assert!(segments.iter().all(|s| source.contains(&s.text.as_ptr())));
+        assert_eq!(MCText::parse(text).spans().len(), segments.len());
+    }
+
+    #[test]
+    fn test_repair_section_signs() {
+        let expected = MCText::parse("§cRed §lBold");
+        for broken in [
+            "Â§cRed Â§lBold",
+            "Ã‚Â§cRed Ã‚Â§lBold",
+            "\\u00a7cRed \\u00A7lBold",
+            "&#167;cRed &sect;lBold",
+        ] {
+            assert_eq!(repair_section_signs(broken), "§cRed §lBold");
+            assert_eq!(MCText::parse_lenient(broken), expected);
+        }
+        assert_eq!(repair_section_signs("plain Â text"), "plain Â text");
+    }
+
+    #[test]
+    #[cfg(feature = "bedrock-colors")]
+    fn test_bedrock_codes() {
+        let text = "§gCoins §uAmethyst§z!";
+        let parsed = MCText::parse(text);
+        assert_eq!(
+            parsed.spans()[0].color,
+            Some(TextColor::from(BedrockColor::MinecoinGold))
+        );
+        assert_eq!(parsed.spans()[1].color.unwrap().to_hex(), "#9A5CC6");
+        assert_eq!(strip_codes(text), "Coins Amethyst!");
+        assert_eq!(count_visible_chars(text), 15);
+        assert!(MCText::parse("§mStruck").spans()[0].style.strikethrough);
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let text = MCText::new()
+            .span("Hi")
+            .color(NamedColor::Red)
+            .bold()
+            .then(" plain")
+            .build();
+        assert_eq!(text.to_ansi(), "\x1b[38;2;255;85;85m\x1b[1mHi\x1b[0m plain");
+        assert_eq!(text.to_ansi_256(), "\x1b[38;5;203m\x1b[1mHi\x1b[0m plain");
+    }
+
+    #[test]
+    fn test_to_html() {
+        let text =
+            MCText::parse("\u{00A7}c\u{00A7}l\u{00A7}nA & <B>\nC\u{00A7}r\u{00A7}kD\u{00A7}rE");
+        assert_eq!(
+            text.to_html(),
+            "<span style=\"color:#ff5555; font-weight:bold; text-decoration:underline\">\
+             A &amp; &lt;B&gt;<br>C</span><span class=\"mc-obfuscated\">D</span>E"
+        );
+    }
+
+    #[test]
+    fn test_normalized_equality() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let parsed = MCText::parse("\u{00A7}cAB");
+        let built = MCText::new()
+            .span("A")
+            .color(NamedColor::Red)
+            .then("")
+            .then("B")
+            .color(NamedColor::Red)
+            .build();
+        let hash = |text: &MCText| {
+            let mut hasher = DefaultHasher::new();
+            text.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(built.spans().len(), 3);
+        assert_eq!(parsed, built);
+        assert_eq!(hash(&parsed), hash(&built));
+        assert_ne!(parsed, MCText::parse("\u{00A7}9AB"));
+    }
+
+    #[test]
+    fn test_lines() {
+        let text = MCText::parse("\u{00A7}cred\nstill red\u{00A7}9\nblue\n");
+        let lines = text.lines();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].plain_text(), "red");
+        assert_eq!(lines[1].plain_text(), "still red");
+        assert_eq!(
+            lines[1].spans()[0].color,
+            Some(TextColor::Named(NamedColor::Red))
+        );
+        assert_eq!(
+            lines[2].spans()[0].color,
+            Some(TextColor::Named(NamedColor::Blue))
+        );
+
+        assert_eq!(MCText::parse("a\n\n").lines().len(), 2);
+        assert_eq!(MCText::parse("a").lines().len(), 1);
+    }
+
+    #[test]
+    fn test_trim() {
+        let text = MCText::new()
+            .span("  ")
+            .then("  hello ")
+            .color(NamedColor::Red)
+            .then(" world  ")
+            .bold()
+            .then(" ")
+            .build();
+        let trimmed = text.trim();
+
+        assert_eq!(trimmed.plain_text(), text.plain_text().trim());
+        assert_eq!(trimmed.spans().len(), 2);
+        assert_eq!(
+            trimmed.spans()[0].color,
+            Some(TextColor::Named(NamedColor::Red))
+        );
+        assert_eq!(text.trim_start().plain_text(), "hello  world   ");
+        assert_eq!(text.trim_end().plain_text(), "    hello  world");
+    }
+
+    #[test]
+    fn test_encoded_lengths() {
+        let text = MCText::parse("\u{00A7}ca\u{00E9}\u{00A7}l\u{1F600}");
+        assert_eq!(text.char_count(), 3);
+        assert_eq!(text.byte_len(), 7);
+        assert_eq!(text.utf16_len(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn test_grapheme_count() {
+        let text = "\u{00A7}ce\u{0301}\u{1F469}\u{200D}\u{1F467}!";
+        assert_eq!(count_visible_chars(text), 6);
+        assert_eq!(count_visible_graphemes(text), 3);
+        assert_eq!(MCText::parse(text).grapheme_count(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn test_terminal_width() {
+        let text = MCText::parse("\u{00A7}aHi \u{00A7}c\u{4F60}\u{597D}!");
+        assert_eq!(text.char_count(), 6);
+        assert_eq!(text.terminal_width(), 8);
+    }
+
+    #[test]
+    fn test_color_resets_format() {
+        let resetting = ParseOptions::default();
+        let keeping = ParseOptions::default().with_color_resets_format(false);
+        let red = Some(TextColor::Named(NamedColor::Red));
+
+        let text = MCText::parse_with_options("\u{00A7}k\u{00A7}cX", &resetting);
+        assert_eq!(text.spans()[0].color, red);
+        assert!(!text.spans()[0].style.obfuscated);
+
+        let text = MCText::parse_with_options("\u{00A7}k\u{00A7}cX", &keeping);
+        assert_eq!(text.spans()[0].color, red);
+        assert!(text.spans()[0].style.obfuscated);
+
+        let mixed = MCText::new()
+            .span("a")
+            .bold()
+            .then("b")
+            .color(NamedColor::Red)
+            .bold()
+            .then("c")
+            .color(NamedColor::Blue)
+            .then("d")
+            .italic()
+            .build();
+        for options in [resetting, keeping] {
+            let legacy = mixed.to_legacy_with(&options);
+            assert_eq!(MCText::parse_with_options(&legacy, &options), mixed);
+        }
+    }
+
     #[test]
     fn test_builder() {
         let text = MCText::new()
@@ -315,6 +1773,371 @@ mod tests {
         assert_eq!(text.plain_text(), "Hello World");
     }
 
+    #[test]
+    fn test_builder_append() {
+        let prefix = MCText::new()
+            .span("[Server] ")
+            .color(NamedColor::Gold)
+            .build();
+        let text = MCText::new()
+            .span(">> ")
+            .color(NamedColor::Red)
+            .bold()
+            .append(prefix.clone())
+            .then("hello")
+            .build();
+
+        assert_eq!(text.plain_text(), ">> [Server] hello");
+        assert_eq!(text.spans().len(), 3);
+        assert_eq!(text.spans()[1], prefix.spans()[0]);
+        assert_eq!(text.spans()[2].color, None);
+        assert!(!text.spans()[2].style.bold);
+    }
+
+    #[test]
+    fn test_format_accumulation_and_reset() {
+        let bold_underlined = Style {
+            bold: true,
+            underlined: true,
+            ..Default::default()
+        };
+        let text = MCText::parse("§l§nX§rY");
+        assert_eq!(text.spans()[0].style, bold_underlined);
+        assert_eq!(text.spans()[1].style, Style::default());
+        assert_eq!(text.spans()[1].color, None);
+        assert_eq!(text.to_legacy(), "§l§nX§rY");
+
+        let text = MCText::parse("§aBold?§lBold§cReset");
+        let styles: Vec<_> = text
+            .spans()
+            .iter()
+            .map(|s| (s.text.as_str(), s.color, s.style.bold))
+            .collect();
+        let green = Some(TextColor::Named(NamedColor::Green));
+        let red = Some(TextColor::Named(NamedColor::Red));
+        assert_eq!(
+            styles,
+            vec![
+                ("Bold?", green, false),
+                ("Bold", green, true),
+                ("Reset", red, false)
+            ]
+        );
+        assert_eq!(MCText::parse(&text.to_legacy()), text);
+
+        let text = MCText::parse("§lA§oB§r§nC");
+        assert!(text.spans()[1].style.bold && text.spans()[1].style.italic);
+        assert!(!text.spans()[2].style.bold && text.spans()[2].style.underlined);
+        assert_eq!(MCText::parse(&text.to_legacy()), text);
+    }
+
+    #[test]
+    fn test_gradient() {
+        let text = MCText::new()
+            .span("abc")
+            .gradient(TextColor::from((255, 0, 0)), TextColor::from((0, 0, 255)))
+            .then("d")
+            .gradient(NamedColor::Red, NamedColor::Blue)
+            .build();
+
+        let colors: Vec<_> = text
+            .spans()
+            .iter()
+            .map(|s| s.color.unwrap().rgb())
+            .collect();
+        assert_eq!(
+            colors,
+            vec![(255, 0, 0), (128, 0, 128), (0, 0, 255), (255, 85, 85)]
+        );
+    }
+
+    #[test]
+    fn test_map_spans() {
+        let text = MCText::parse("§c§lHello §9§oWorld");
+        let bold = text.clone().map_spans(|mut span| {
+            span.style.bold = true;
+            span
+        });
+        assert!(bold.spans().iter().all(|s| s.style.bold));
+        assert_eq!(bold.plain_text(), "Hello World");
+
+        let gold = text.clone().with_color_override(NamedColor::Gold);
+        assert!(
+            gold.spans()
+                .iter()
+                .all(|s| s.color == Some(TextColor::Named(NamedColor::Gold)))
+        );
+
+        let plain = text.strip_colors().strip_styles();
+        assert_eq!(plain, MCText::new().span("Hello World").build());
+    }
+
+    #[test]
+    fn test_char_span_positions() {
+        let text = MCText::new().span("hé").then("").then("llo").build();
+        assert_eq!(text.char_to_span_pos(0), Some((0, 0)));
+        assert_eq!(text.char_to_span_pos(1), Some((0, 1)));
+        assert_eq!(text.char_to_span_pos(2), Some((2, 0)));
+        assert_eq!(text.char_to_span_pos(5), Some((2, 3)));
+        assert_eq!(text.char_to_span_pos(6), None);
+        assert_eq!(MCText::new().char_to_span_pos(0), None);
+
+        for index in 0..=text.char_count() {
+            let (span, offset) = text.char_to_span_pos(index).unwrap();
+            assert_eq!(text.span_pos_to_char(span, offset), index);
+        }
+        assert_eq!(text.span_pos_to_char(0, 3), 2);
+    }
+
+    #[test]
+    fn test_unknown_code_policies() {
+        let parse = |policy| {
+            MCText::parse_with_options("§ca§zb", &ParseOptions::default().with_unknown_code(policy))
+        };
+        assert_eq!(parse(UnknownCode::Drop).plain_text(), "ab");
+        assert_eq!(parse(UnknownCode::Keep).plain_text(), "a§zb");
+        assert_eq!(parse(UnknownCode::Literal).plain_text(), "azb");
+
+        let kept = parse(UnknownCode::Keep);
+        assert_eq!(kept.spans().len(), 1);
+        assert_eq!(kept.to_legacy(), "§ca§zb");
+        assert_eq!(MCText::parse("a§zb").plain_text(), "ab");
+        assert_eq!(MCText::parse_amp("Tom & Jerry").plain_text(), "Tom & Jerry");
+    }
+
+    #[test]
+    fn test_to_legacy_rgb_modes() {
+        let text = MCText::new()
+            .span("warm")
+            .rgb(250, 80, 90)
+            .bold()
+            .then("plain")
+            .build();
+        assert_eq!(text.to_legacy(), "§x§f§a§5§0§5§a§lwarm§rplain");
+        let config = ParseOptions::default()
+            .with_prefix('&')
+            .with_hex_colors(false);
+        assert_eq!(text.to_legacy_with(&config), "&c&lwarm&rplain");
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let text = MCText::parse("§cRed §9Blue");
+        let spans = text.clone().into_spans();
+        assert_eq!(MCText::from_spans(spans.clone()), text);
+
+        let upper: MCText = text
+            .into_iter()
+            .map(|span| Span {
+                text: span.text.to_uppercase(),
+                ..span
+            })
+            .collect();
+        assert_eq!(upper.plain_text(), "RED BLUE");
+        assert_eq!(upper.spans().len(), spans.len());
+    }
+
+    #[test]
+    fn test_is_plain_and_has_rgb() {
+        assert!(MCText::parse("just text").is_plain());
+        assert!(MCText::new().is_plain());
+        assert!(!MCText::parse("§lbold").is_plain());
+        assert!(!MCText::parse("§cred").has_rgb());
+        assert!(MCText::parse("§x§f§f§0§0§0§0hex").has_rgb());
+
+        let linked = MCText::new()
+            .span("site")
+            .build()
+            .map_spans(|span| span.with_insertion("site"));
+        assert!(!linked.is_plain());
+        assert!(!MCText::new().span("a").rgb(1, 2, 3).build().is_plain());
+    }
+
+    #[test]
+    fn test_rainbow() {
+        let colors = |text: MCText| -> Vec<_> {
+            text.spans()
+                .iter()
+                .map(|s| s.color.unwrap().rgb())
+                .collect()
+        };
+        let rainbow = MCText::new().span("abc").rainbow().build();
+        assert_eq!(colors(rainbow), vec![(255, 0, 0), (0, 255, 0), (0, 0, 255)]);
+
+        let shifted = MCText::new().span("abc").rainbow_from(1.0 / 3.0).build();
+        assert_eq!(colors(shifted), vec![(0, 255, 0), (0, 0, 255), (255, 0, 0)]);
+
+        let muted = MCText::new().span("a").rainbow_with(0.5, 0.5).build();
+        assert_eq!(colors(muted), vec![(128, 64, 64)]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_roundtrip() {
+        let text = MCText::parse("§x§1§2§3§4§5§6Hex §c§lBold §rplain");
+        let json = serde_json::to_string(&text).unwrap();
+        assert_eq!(serde_json::from_str::<MCText>(&json).unwrap(), text);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_resolve_translations() {
+        let arrow = Translation::new("death.attack.arrow").with_args(vec![
+            MCText::new()
+                .span("Steve")
+                .color(NamedColor::Yellow)
+                .build(),
+            MCText::new().span("Skeleton").build(),
+        ]);
+        let mut text = MCText::new();
+        text.push(
+            Span::new("death.attack.arrow")
+                .with_color(NamedColor::Red)
+                .with_translation(arrow),
+        );
+        assert_eq!(text.plain_text(), "death.attack.arrow");
+
+        let mut table = HashMap::new();
+        table.insert(
+            "death.attack.arrow".to_string(),
+            "%1$s was shot by %2$s (100%%)".to_string(),
+        );
+        let resolved = text.resolve_translations(&table);
+        assert_eq!(resolved.plain_text(), "Steve was shot by Skeleton (100%)");
+        assert_eq!(
+            resolved.spans()[0].color,
+            Some(TextColor::Named(NamedColor::Yellow))
+        );
+        assert_eq!(
+            resolved.spans()[2].color,
+            Some(TextColor::Named(NamedColor::Red))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_substitute_tokens() {
+        let mut table = HashMap::new();
+        table.insert("smile".to_string(), '\u{E000}');
+        table.insert("heart".to_string(), '\u{E001}');
+
+        let text = MCText::parse("\u{00A7}ehi :smile: at 12:30 :nope:\u{00A7}c:heart::smile:");
+        let substituted = text.substitute_tokens(&table);
+        assert_eq!(
+            substituted.plain_text(),
+            "hi \u{E000} at 12:30 :nope:\u{E001}\u{E000}"
+        );
+        assert_eq!(substituted.spans().len(), text.spans().len());
+        assert_eq!(substituted.spans()[1].color, text.spans()[1].color);
+    }
+
+    #[test]
+    fn test_scramble_obfuscated() {
+        let text = MCText::parse("§7a §kab cd§r e");
+        let first = text.scramble_obfuscated(1);
+        assert_eq!(first, text.scramble_obfuscated(1));
+        assert_ne!(first, text.scramble_obfuscated(2));
+        assert_eq!(first.spans()[0], text.spans()[0]);
+        assert_eq!(first.spans()[2], text.spans()[2]);
+
+        let scrambled = &first.spans()[1].text;
+        assert_eq!(scrambled.chars().count(), 5);
+        assert_eq!(scrambled.chars().nth(2), Some(' '));
+    }
+
+    #[test]
+    fn test_styled_chars() {
+        let text = MCText::parse("\u{00A7}cab\u{00A7}lc");
+        let chars: Vec<_> = text.styled_chars().collect();
+        assert_eq!(chars.len(), text.char_count());
+        assert_eq!(
+            chars[1],
+            (
+                'b',
+                Some(TextColor::Named(NamedColor::Red)),
+                Style::default()
+            )
+        );
+        assert!(chars[2].2.bold);
+    }
+
+    #[test]
+    fn test_replace() {
+        let text = MCText::parse("\u{00A7}cHi {player}\u{00A7}l!");
+        let replaced = text.replace("{player}", "Steve");
+        assert_eq!(replaced.plain_text(), "Hi Steve!");
+        let steve = replaced.spans().iter().find(|s| s.text == "Steve").unwrap();
+        assert_eq!(steve.color, Some(TextColor::Named(NamedColor::Red)));
+
+        let replaced = text.replace_with("{player}", |_| {
+            MCText::new()
+                .span("Alex")
+                .color(NamedColor::Gold)
+                .bold()
+                .build()
+        });
+        let alex = replaced.spans().iter().find(|s| s.text == "Alex").unwrap();
+        assert_eq!(alex.color, Some(TextColor::Named(NamedColor::Gold)));
+        assert!(alex.style.bold);
+
+        let split = MCText::parse("a{pl\u{00A7}9ayer}b").replace("{player}", "X");
+        assert_eq!(split.plain_text(), "aXb");
+        assert_eq!(split.spans()[1].color, None);
+
+        let mut text = MCText::new().span("test ").build();
+        text.push(Span::new("chat.type.text").with_translation(Translation::new("chat.type.text")));
+        text.push(Span::new("@p").with_content(ComponentContent::Selector {
+            selector: "@p".into(),
+            separator: None,
+        }));
+        let replaced = text.replace("t", "X").replace("p", "q");
+        assert_eq!(replaced.plain_text(), "XesX chat.type.text@p");
+        assert_eq!(replaced.spans().iter().filter(|s| s.is_atomic()).count(), 2);
+        assert_eq!(
+            &replaced.spans()[replaced.spans().len() - 2..],
+            &text.spans()[1..]
+        );
+    }
+
+    #[test]
+    fn test_slice() {
+        let text = MCText::parse("§cHello §9Wörld");
+        let sliced = text.slice(3..8);
+        assert_eq!(sliced.plain_text(), "lo Wö");
+        assert_eq!(sliced.spans().len(), 2);
+        assert_eq!(sliced.spans()[0].color, text.spans()[0].color);
+        assert_eq!(sliced.spans()[1].color, text.spans()[1].color);
+
+        assert_eq!(text.slice(8..100).plain_text(), "rld");
+        assert!(text.slice(4..4).is_empty());
+        assert!(text.slice(50..60).is_empty());
+
+        let mut text = MCText::new().span("ab").build();
+        text.push(Span::new("chat.type.text").with_translation(Translation::new("chat.type.text")));
+        text.push(Span::new("cd"));
+        let head = text.slice(0..4);
+        assert_eq!(head.plain_text(), "ab");
+        assert!(head.spans().iter().all(|s| s.translate.is_none()));
+        let whole = text.slice(0..18);
+        assert_eq!(
+            whole
+                .spans()
+                .iter()
+                .filter(|s| s.translate.is_some())
+                .count(),
+            1
+        );
+        let joined = text.slice(0..9).concat(text.slice(9..usize::MAX));
+        assert_eq!(
+            joined
+                .spans()
+                .iter()
+                .filter(|s| s.translate.is_some())
+                .count(),
+            0
+        );
+    }
+
     #[test]
     fn test_concat() {
         let a = MCText::new().span("Hello ").color(NamedColor::Red).build();
@@ -322,4 +2145,15 @@ mod tests {
 
         assert_eq!((a + b).plain_text(), "Hello World");
     }
+
+    #[test]
+    fn test_extend() {
+        let a = MCText::new().span("Hello ").color(NamedColor::Red).build();
+        let b = MCText::new().span("World").color(NamedColor::Blue).build();
+        assert_eq!(a.clone().concat(MCText::new()), a);
+
+        let mut extended = a.clone();
+        extended.extend(b.clone());
+        assert_eq!(extended.spans(), (a + b).spans());
+    }
 }
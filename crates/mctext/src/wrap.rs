@@ -0,0 +1,412 @@
+use crate::math;
+use crate::text::{MCText, Span};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "render")]
+use crate::fonts::FontVariant;
+#[cfg(feature = "render")]
+use crate::system::FontSystem;
+
+#[cfg(feature = "render")]
+pub const SIGN_LINE_WIDTH: u32 = 90;
+
+pub(crate) enum Token<T> {
+    Word(Vec<T>),
+    Atomic(Vec<T>),
+    Space(T),
+    Newline,
+}
+
+pub(crate) const TAB_SPACES: f32 = 4.0;
+
+pub(crate) fn tab_advance(cursor_x: f32, tab_width: f32) -> f32 {
+    if tab_width <= 0.0 {
+        return 0.0;
+    }
+    (math::floor(cursor_x / tab_width) + 1.0) * tab_width - cursor_x
+}
+
+pub(crate) fn break_lines<T>(
+    tokens: Vec<Token<T>>,
+    max_width: Option<f32>,
+    advance: impl Fn(&mut T, f32) -> f32,
+) -> (Vec<Vec<T>>, f32) {
+    let mut lines: Vec<Vec<T>> = vec![Vec::new()];
+    let mut cursor_x = 0.0f32;
+    let mut widest = 0.0f32;
+    let mut wrapped = false;
+
+    let mut new_line = |lines: &mut Vec<Vec<T>>, cursor_x: &mut f32| {
+        widest = widest.max(*cursor_x);
+        *cursor_x = 0.0;
+        lines.push(Vec::new());
+    };
+
+    for token in tokens {
+        match token {
+            Token::Newline => {
+                new_line(&mut lines, &mut cursor_x);
+                wrapped = false;
+            }
+            Token::Space(mut item) => {
+                if wrapped && cursor_x == 0.0 {
+                    continue;
+                }
+                let width = advance(&mut item, cursor_x);
+                if let Some(max_w) = max_width {
+                    if cursor_x + width > max_w && cursor_x > 0.0 {
+                        new_line(&mut lines, &mut cursor_x);
+                        wrapped = true;
+                        continue;
+                    }
+                }
+                lines.last_mut().unwrap().push(item);
+                cursor_x += width;
+            }
+            Token::Word(mut items) => {
+                let word_width: f32 = items.iter_mut().map(|item| advance(item, 0.0)).sum();
+
+                if let Some(max_w) = max_width {
+                    if cursor_x > 0.0 && cursor_x + word_width > max_w {
+                        new_line(&mut lines, &mut cursor_x);
+                    }
+                }
+
+                for mut item in items {
+                    let width = advance(&mut item, cursor_x);
+                    if let Some(max_w) = max_width {
+                        if cursor_x + width > max_w && cursor_x > 0.0 {
+                            new_line(&mut lines, &mut cursor_x);
+                        }
+                    }
+                    wrapped = false;
+                    lines.last_mut().unwrap().push(item);
+                    cursor_x += width;
+                }
+            }
+            Token::Atomic(mut items) => {
+                let width: f32 = items.iter_mut().map(|item| advance(item, 0.0)).sum();
+
+                if let Some(max_w) = max_width {
+                    if cursor_x > 0.0 && cursor_x + width > max_w {
+                        new_line(&mut lines, &mut cursor_x);
+                    }
+                }
+
+                wrapped = false;
+                lines.last_mut().unwrap().extend(items);
+                cursor_x += width;
+            }
+        }
+    }
+
+    widest = widest.max(cursor_x);
+    (lines, widest)
+}
+
+pub(crate) fn tokenize<T>(
+    text: &MCText,
+    mut item: impl FnMut(char, usize, &Span) -> T,
+) -> Vec<Token<T>> {
+    let mut tokens = Vec::new();
+    let mut current_word = Vec::new();
+
+    for (index, span) in text.spans().iter().enumerate() {
+        if span.is_atomic() {
+            if !current_word.is_empty() {
+                tokens.push(Token::Word(core::mem::take(&mut current_word)));
+            }
+            let items: Vec<T> = span
+                .text
+                .chars()
+                .filter(|ch| !ch.is_control())
+                .map(|ch| item(ch, index, span))
+                .collect();
+            if !items.is_empty() {
+                tokens.push(Token::Atomic(items));
+            }
+            continue;
+        }
+
+        for ch in span.text.chars() {
+            match ch {
+                '\n' => {
+                    if !current_word.is_empty() {
+                        tokens.push(Token::Word(core::mem::take(&mut current_word)));
+                    }
+                    tokens.push(Token::Newline);
+                }
+                ' ' | '\t' => {
+                    if !current_word.is_empty() {
+                        tokens.push(Token::Word(core::mem::take(&mut current_word)));
+                    }
+                    tokens.push(Token::Space(item(ch, index, span)));
+                }
+                _ if !ch.is_control() => {
+                    current_word.push(item(ch, index, span));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if !current_word.is_empty() {
+        tokens.push(Token::Word(current_word));
+    }
+
+    tokens
+}
+
+impl MCText {
+    pub fn wrap_with(&self, max_width: f32, measure: impl Fn(char, &Span) -> f32) -> Vec<MCText> {
+        let spans = self.spans();
+        let tokens = tokenize(self, |ch, index, span| (ch, index, measure(ch, span)));
+        let (lines, _) = break_lines(tokens, Some(max_width), |item, cursor_x| {
+            if item.0 == '\t' {
+                let tab_width = TAB_SPACES * measure(' ', &spans[item.1]);
+                item.2 = tab_advance(cursor_x, tab_width);
+            }
+            item.2
+        });
+
+        lines
+            .into_iter()
+            .map(|line| {
+                let mut text = MCText::new();
+                let mut current: Option<(usize, String)> = None;
+
+                for (ch, span_index, _) in line {
+                    match &mut current {
+                        Some((index, buffer)) if *index == span_index => buffer.push(ch),
+                        _ => {
+                            if let Some((index, buffer)) = current.take() {
+                                text.push(Span {
+                                    text: buffer,
+                                    ..spans[index].clone()
+                                });
+                            }
+                            current = Some((span_index, ch.to_string()));
+                        }
+                    }
+                }
+                if let Some((index, buffer)) = current {
+                    text.push(Span {
+                        text: buffer,
+                        ..spans[index].clone()
+                    });
+                }
+
+                text
+            })
+            .collect()
+    }
+
+    pub fn truncate_with(
+        &self,
+        max_width: f32,
+        ellipsis: &str,
+        measure: impl Fn(char, &Span) -> f32,
+    ) -> MCText {
+        let total: f32 = self
+            .spans()
+            .iter()
+            .flat_map(|span| span.text.chars().map(|ch| measure(ch, span)))
+            .sum();
+        if total <= max_width {
+            return self.clone();
+        }
+
+        let ellipsis_span = |color| Span {
+            color,
+            ..Default::default()
+        };
+        let ellipsis_width: f32 = {
+            let span = ellipsis_span(None);
+            ellipsis.chars().map(|ch| measure(ch, &span)).sum()
+        };
+
+        let mut result = MCText::new();
+        let mut cursor_x = 0.0f32;
+        let mut last_color = self.spans().first().and_then(|span| span.color);
+
+        for span in self.spans() {
+            let mut kept = String::new();
+            let mut overflowed = false;
+            for ch in span.text.chars() {
+                let width = measure(ch, span);
+                if cursor_x + width + ellipsis_width > max_width {
+                    overflowed = true;
+                    break;
+                }
+                cursor_x += width;
+                kept.push(ch);
+            }
+            if !kept.is_empty() {
+                last_color = span.color;
+                result.push(Span {
+                    text: kept,
+                    ..span.clone()
+                });
+            }
+            if overflowed {
+                break;
+            }
+        }
+
+        let span = ellipsis_span(last_color);
+        let mut text = String::new();
+        for ch in ellipsis.chars() {
+            let width = measure(ch, &span);
+            if cursor_x + width > max_width {
+                break;
+            }
+            cursor_x += width;
+            text.push(ch);
+        }
+        if !text.is_empty() {
+            result.push(Span { text, ..span });
+        }
+
+        result
+    }
+
+    #[cfg(feature = "render")]
+    pub fn wrap(&self, font_system: &FontSystem, size: f32, max_width: f32) -> Vec<MCText> {
+        self.wrap_with(max_width, |ch, span| {
+            let variant = FontVariant::from_style(span.style.bold, span.style.italic);
+            font_system.measure_char(ch, size, variant)
+        })
+    }
+
+    #[cfg(feature = "render")]
+    pub fn fits_sign_line(&self, font_system: &FontSystem) -> bool {
+        let mut width = 0;
+        for (ch, _, style) in self.styled_chars() {
+            if ch == '\n' {
+                return false;
+            }
+            if !ch.is_control() {
+                width += font_system.mc_pixel_advance(ch, style.bold);
+            }
+        }
+        width <= SIGN_LINE_WIDTH
+    }
+
+    #[cfg(feature = "render")]
+    pub fn split_to_sign_lines(&self, font_system: &FontSystem) -> Vec<MCText> {
+        self.wrap_with(SIGN_LINE_WIDTH as f32, |ch, span| {
+            font_system.mc_pixel_advance(ch, span.style.bold) as f32
+        })
+        .iter()
+        .map(MCText::trim_end)
+        .collect()
+    }
+
+    #[cfg(feature = "render")]
+    pub fn truncate_to_width(
+        &self,
+        font_system: &FontSystem,
+        size: f32,
+        max_width: f32,
+        ellipsis: &str,
+    ) -> MCText {
+        self.truncate_with(max_width, ellipsis, |ch, span| {
+            let variant = FontVariant::from_style(span.style.bold, span.style.italic);
+            font_system.measure_char(ch, size, variant)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{NamedColor, TextColor};
+    use crate::text::Translation;
+
+    #[test]
+    fn test_wrap_with() {
+        let text = MCText::new()
+            .span("hello ")
+            .color(NamedColor::Red)
+            .then("big world")
+            .color(NamedColor::Blue)
+            .then(" abcdefghij")
+            .build();
+        let lines = text.wrap_with(6.0, |_, _| 1.0);
+        let plain: Vec<_> = lines.iter().map(|l| l.plain_text()).collect();
+
+        assert_eq!(plain, vec!["hello ", "big ", "world ", "abcdef", "ghij"]);
+        assert_eq!(lines[1].spans()[0].color, text.spans()[1].color);
+
+        let lines = MCText::parse("abcd  ef").wrap_with(4.0, |_, _| 1.0);
+        let plain: Vec<_> = lines.iter().map(|l| l.plain_text()).collect();
+        assert_eq!(plain, vec!["abcd", "ef"]);
+
+        let mut text = MCText::new().span("hi ").build();
+        text.push(Span::new("chat.type.text").with_translation(Translation::new("chat.type.text")));
+        text.push(Span::new(" ok"));
+        let lines = text.wrap_with(5.0, |_, _| 1.0);
+        let plain: Vec<_> = lines.iter().map(|l| l.plain_text()).collect();
+        assert_eq!(plain, vec!["hi ", "chat.type.text", "ok"]);
+        let translated: usize = lines
+            .iter()
+            .map(|l| l.spans().iter().filter(|s| s.translate.is_some()).count())
+            .sum();
+        assert_eq!(translated, 1);
+    }
+
+    #[test]
+    #[cfg(feature = "modern-fonts")]
+    fn test_sign_lines() {
+        let system = FontSystem::modern();
+        assert!(MCText::parse("§cShort line").fits_sign_line(&system));
+        assert!(!MCText::parse("a\nb").fits_sign_line(&system));
+
+        let text = MCText::parse("§6Welcome to the §lserver§r§6, please read the rules");
+        assert!(!text.fits_sign_line(&system));
+        let lines = text.split_to_sign_lines(&system);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| line.fits_sign_line(&system)));
+        assert_eq!(lines[0].spans()[0].color, text.spans()[0].color);
+        let rejoined: Vec<_> = lines.iter().map(MCText::plain_text).collect();
+        assert_eq!(rejoined.join(" "), text.plain_text());
+    }
+
+    #[test]
+    fn test_tab_advance() {
+        assert_eq!(tab_advance(0.0, 4.0), 4.0);
+        assert_eq!(tab_advance(5.0, 4.0), 3.0);
+        assert_eq!(tab_advance(8.0, 4.0), 4.0);
+
+        let text = MCText::new().span("a\tb\tc").build();
+        let lines = text.wrap_with(8.5, |_, _| 1.0);
+        let plain: Vec<_> = lines.iter().map(|l| l.plain_text()).collect();
+        assert_eq!(plain, vec!["a\tb\t", "c"]);
+    }
+
+    #[test]
+    fn test_truncate_with() {
+        let text = MCText::new()
+            .span("abc")
+            .color(NamedColor::Red)
+            .then("defgh")
+            .color(NamedColor::Blue)
+            .build();
+
+        assert_eq!(text.truncate_with(8.0, "...", |_, _| 1.0), text);
+
+        let truncated = text.truncate_with(7.0, "...", |_, _| 1.0);
+        assert_eq!(truncated.plain_text(), "abcd...");
+        assert_eq!(
+            truncated.spans().last().unwrap().color,
+            Some(TextColor::Named(NamedColor::Blue))
+        );
+
+        assert_eq!(
+            text.truncate_with(2.0, "...", |_, _| 1.0).plain_text(),
+            ".."
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use mctext::{
-    FontSystem, FontVersion, LayoutOptions, MCText, NamedColor, SoftwareRenderer, TextRenderContext,
+    FontSystem, FontVersion, LayoutOptions, MCText, NamedColor, SoftwareRenderer,
+    TextRenderContext, blend_image_over,
 };
 
 fn main() {
@@ -23,8 +24,10 @@ fn main() {
         pixel[3] = 255;
     }
 
-    let mut renderer = SoftwareRenderer::new(&fonts, &mut buffer, width, height);
-    let _ = TextRenderContext::new(&fonts).render(&mut renderer, &text, 10.0, 14.0, &options);
+    let mut renderer = SoftwareRenderer::with_size(&fonts, width, height);
+    let _ = TextRenderContext::new(&fonts).render(&mut renderer, &text, 0.0, 0.0, &options);
+    let layer = renderer.into_buffer();
+    blend_image_over(&mut buffer, width, &layer, width, height, 10, 14);
 
     image::save_buffer(
         "rust_output.png",
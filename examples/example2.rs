@@ -1,5 +1,6 @@
 use mctext::{
-    FontSystem, FontVersion, LayoutOptions, MCText, NamedColor, SoftwareRenderer, TextRenderContext,
+    FontSystem, FontVersion, LayoutOptions, MCText, NamedColor, SoftwareRenderer,
+    TextRenderContext, blend_image_over,
 };
 
 fn main() {
@@ -36,11 +37,13 @@ fn main() {
         pixel[3] = 255;
     }
 
-    let mut renderer = SoftwareRenderer::new(&fonts, &mut buffer, width, height);
     let ctx = TextRenderContext::new(&fonts);
-    let _ = ctx.render(&mut renderer, &legacy, 10.0, 6.0, &options);
-    let _ = ctx.render(&mut renderer, &bold, 10.0, 36.0, &options);
-    let _ = ctx.render(&mut renderer, &wrapped, 10.0, 66.0, &options);
+    for (text, y) in [(&legacy, 6), (&bold, 36), (&wrapped, 66)] {
+        let mut renderer = SoftwareRenderer::with_size(&fonts, width, height);
+        let _ = ctx.render(&mut renderer, text, 0.0, 0.0, &options);
+        let layer = renderer.into_buffer();
+        blend_image_over(&mut buffer, width, &layer, width, height, 10, y);
+    }
 
     image::save_buffer(
         "rust_output2.png",